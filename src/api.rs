@@ -0,0 +1,52 @@
+// Copyright (c) 2024 inunix3
+//
+// This file is licensed under the MIT License (see LICENSE.md).
+
+use crate::{
+    app::Application,
+    error::DshwError,
+    query::{Query, QueryOptions, Target},
+    units::DataUnit,
+    value::Value,
+};
+
+/// Executes the queries against the target and returns their values in the same order. Amounts of
+/// information are returned in bytes.
+///
+/// ```no_run
+/// use dshw::{query::MemoryQuery, Target};
+///
+/// let values = dshw::query(Target::Memory, &[MemoryQuery::Usage, MemoryQuery::Total]).unwrap();
+/// ```
+pub fn query<Q>(target: Target, queries: &[Q]) -> Result<Vec<Value>, DshwError>
+where
+    Q: Clone + Into<Query>,
+{
+    query_with_unit(target, queries, DataUnit::Bytes)
+}
+
+/// Same as [`query`], but amounts of information are returned in the given unit.
+pub fn query_with_unit<Q>(
+    target: Target,
+    queries: &[Q],
+    data_unit: DataUnit,
+) -> Result<Vec<Value>, DshwError>
+where
+    Q: Clone + Into<Query>,
+{
+    query_with_options(target, queries, &QueryOptions::new(data_unit))
+}
+
+/// Same as [`query`], but values are presented according to the options (units, time format).
+pub fn query_with_options<Q>(
+    target: Target,
+    queries: &[Q],
+    options: &QueryOptions,
+) -> Result<Vec<Value>, DshwError>
+where
+    Q: Clone + Into<Query>,
+{
+    let queries: Vec<Query> = queries.iter().cloned().map(Into::into).collect();
+
+    Application::new().query(&target, &queries, options)
+}
@@ -3,27 +3,69 @@
 // This file is licensed under the MIT License (see LICENSE.md).
 
 use crate::{
-    cli::{Cli, CliCommand},
+    check::{Check, Status},
+    cli::{CheckArgs, Cli, CliCommand, InfoCommand},
     cmd::*,
-    query::Query,
+    color::{paint, Painter},
+    completions::generate_script,
+    cpustat::{cpu_times, CpuTimes},
+    drive::find_drive,
+    error::DshwError,
+    filter::Pipeline,
+    hwmon::{self, Channel},
+    pressure::{cgroup_dir, pressure},
+    query::{Query, QueryOptions, Target},
+    schedule::Scheduler,
+    summary::Summary,
+    swap::swap_devices,
+    units::DataUnit,
+    value::Value,
+    watch::Watcher,
 };
 
-use anyhow::{Context, Result};
+use anyhow::Result;
 use once_cell::unsync::Lazy;
 use regex::{Captures, Regex};
 use sysinfo::{Components, Disks, Networks, System};
 use unescaper::unescape;
 
-use std::{collections::HashMap, thread};
+use std::{
+    collections::{hash_map::Entry, HashMap, HashSet},
+    process::ExitCode,
+    sync::mpsc,
+    time::{Duration, Instant},
+};
 
 type FmtContext = HashMap<String, String>;
 
+/// Determine if the error of a watched command may go away on the next poll. Unknown entities are
+/// considered transient only if the entity has been `found` before.
+fn is_transient(e: &anyhow::Error, found: bool) -> bool {
+    match e.downcast_ref::<DshwError>() {
+        Some(DshwError::Unavailable(_) | DshwError::Io(_)) => true,
+        Some(DshwError::UnknownEntity { .. } | DshwError::AmbiguousEntity { .. }) => found,
+        _ => false,
+    }
+}
+
+/// Polling interval of `watch` if no interval is given.
+const DEFAULT_WATCH_INTERVAL: Duration = Duration::from_secs(1);
+
 #[derive(Debug)]
 pub struct Application {
     pub sys: System,
     pub drives: Lazy<Disks>,
     pub sensors: Lazy<Components>,
+    /// Fan, voltage, current and power sensors, which sysinfo does not provide.
+    pub hwmon: Lazy<Vec<Channel>>,
     pub networks: Lazy<Networks>,
+    /// Filters of format specifiers and `--smooth` rules, which keep their state across runs.
+    pub pipelines: HashMap<String, Pipeline>,
+    /// Times spent by CPUs in each state between the last two refreshes, keyed by CPU name (`cpu`
+    /// for all CPUs).
+    pub cpu_times: HashMap<String, CpuTimes>,
+    /// Times of all CPUs read from `/proc/stat` by the last refresh and when it happened.
+    cpu_stat: Option<(Option<HashMap<String, CpuTimes>>, Instant)>,
 }
 
 impl Default for Application {
@@ -32,7 +74,11 @@ impl Default for Application {
             sys: System::new(),
             drives: Lazy::new(Disks::new_with_refreshed_list),
             sensors: Lazy::new(Components::new_with_refreshed_list),
+            hwmon: Lazy::new(hwmon::channels),
             networks: Lazy::new(Networks::new_with_refreshed_list),
+            pipelines: HashMap::new(),
+            cpu_times: HashMap::new(),
+            cpu_stat: None,
         }
     }
 }
@@ -42,25 +88,100 @@ impl Application {
         Default::default()
     }
 
-    pub fn run(mut self, cli: Cli) -> Result<()> {
-        if cli.run_times == 1 {
-            return self.exec_cmd(&cli);
+    pub fn run(mut self, cli: Cli) -> Result<ExitCode> {
+        match &cli.cmd {
+            CliCommand::Info(cmd) => {
+                let run_times = cli.run_times.unwrap_or(1);
+                let mut scheduler = Scheduler::new(cli.interval.map(Into::into), cli.align);
+                let mut summary = cli.summary.then(Summary::new);
+
+                // Ctrl-C stops the loop instead of killing the program, so the summary of the
+                // values collected so far still gets printed.
+                if summary.is_some() {
+                    let (tx, rx) = mpsc::channel();
+
+                    ctrlc::set_handler(move || {
+                        let _ = tx.send(());
+                    })?;
+
+                    scheduler = scheduler.interruptible(rx);
+                }
+
+                self.repeat(run_times, scheduler, |app| {
+                    let values = app.exec_cmd(&cli, cmd)?;
+
+                    if let Some(summary) = &mut summary {
+                        for (name, value) in &values {
+                            summary.record(name, value);
+                        }
+                    }
+
+                    Ok(())
+                })?;
+
+                if let Some(summary) = summary {
+                    for line in summary.report() {
+                        println!("{}", line);
+                    }
+                }
+            }
+            CliCommand::Watch(args) => {
+                let mut watcher = Watcher::new(args)?;
+                let run_times = cli.run_times.unwrap_or(0);
+                let interval = cli.interval.map_or(DEFAULT_WATCH_INTERVAL, Into::into);
+                let scheduler = Scheduler::new(Some(interval), cli.align);
+
+                // Whether the watched entity has been found at least once.
+                let mut found = false;
+
+                self.repeat(run_times, scheduler, |app| {
+                    match app.exec_watched(&args.cmd, cli.data_unit) {
+                        Ok((query, value)) => {
+                            found = true;
+
+                            watcher.update(&query, &value)
+                        }
+                        // Reads can fail temporarily and entities can disappear for a while (e.g. a
+                        // network interface going down), so only configuration errors stop watching.
+                        Err(e) if is_transient(&e, found) => {
+                            eprintln!("Warning: {:#}", e);
+
+                            Ok(())
+                        }
+                        Err(e) => Err(e),
+                    }
+                })?;
+            }
+            CliCommand::Check(args) => return Ok(self.check(args, cli.data_unit)),
+            CliCommand::Completions { shell } => print!("{}", generate_script(*shell)),
+        }
+
+        Ok(ExitCode::SUCCESS)
+    }
+
+    /// Runs `f` `run_times` times (infinitely if 0) on the ticks of the scheduler.
+    fn repeat<F>(&mut self, run_times: u64, mut scheduler: Scheduler, mut f: F) -> Result<()>
+    where
+        F: FnMut(&mut Self) -> Result<()>,
+    {
+        if !scheduler.start() {
+            return Ok(());
         }
 
         let mut cnt = 0u64;
         loop {
-            if cli.run_times > 0 && cnt >= cli.run_times {
-                break;
-            }
+            f(self)?;
 
-            self.exec_cmd(&cli)?;
+            if run_times != 0 {
+                cnt += 1;
 
-            if let Some(i) = cli.interval {
-                thread::sleep(*i);
+                if cnt >= run_times {
+                    break;
+                }
             }
 
-            if cli.run_times != 0 {
-                cnt += 1;
+            if !scheduler.wait() {
+                break;
             }
         }
 
@@ -69,154 +190,560 @@ impl Application {
 
     pub fn command_from_cli<'a>(
         &'a mut self,
-        cli: &Cli,
+        cli_cmd: &InfoCommand,
+        data_unit: DataUnit,
     ) -> Result<(Box<dyn Command + 'a>, Vec<Query>)> {
-        let cli_cmd = &cli.cmd;
+        if let Some((target, queries)) = cli_cmd.target() {
+            let options = cli_cmd.options(data_unit);
+
+            return Ok((self.command(&target, &options)?, queries));
+        }
 
         match cli_cmd {
-            CliCommand::Os { queries } => Ok((
-                Box::new(OsCommand::new(self)),
-                queries.iter().map(|q| Query::Os(q.clone())).collect(),
+            InfoCommand::ListSensors { list, chip } => Ok((
+                Box::new(ListSensorsCommand::new(self, list.clone(), chip.clone())),
+                vec![],
+            )),
+            InfoCommand::ListCpus { list, .. } => {
+                Ok((Box::new(ListCpusCommand::new(self, list.clone())), vec![]))
+            }
+            InfoCommand::ListNetworks {
+                list,
+                physical,
+                r#virtual,
+                up,
+            } => {
+                let physical = match (physical, r#virtual) {
+                    (true, _) => Some(true),
+                    (_, true) => Some(false),
+                    _ => None,
+                };
+
+                Ok((
+                    Box::new(ListNetworksCommand::new(self, list.clone(), physical, *up)),
+                    vec![],
+                ))
+            }
+            InfoCommand::ListDrives { list } => Ok((
+                Box::new(ListDrivesCommand::new(self, list.clone(), false)),
+                vec![],
             )),
-            CliCommand::Cpu { name, queries } => {
-                self.refresh_cpus();
+            InfoCommand::ListSwaps { list } => {
+                Ok((Box::new(ListSwapsCommand::new(list.clone())), vec![]))
+            }
+            // Commands with a target have been handled above.
+            _ => Err(DshwError::invalid_query("", "not a list command").into()),
+        }
+    }
+
+    /// Creates the command which answers queries about the target. Refreshes the needed
+    /// information beforehand.
+    pub fn command<'a>(
+        &'a mut self,
+        target: &Target,
+        options: &QueryOptions,
+    ) -> Result<Box<dyn Command + 'a>, DshwError> {
+        self.refresh(target);
+
+        self.build_command(target, options)
+    }
+
+    /// Refreshes the information about the target needed by its command.
+    pub fn refresh(&mut self, target: &Target) {
+        match target {
+            Target::Cpu(_) => self.refresh_cpus(),
+            Target::Memory | Target::Swap => self.sys.refresh_memory(),
+            Target::Drive { .. } => self.drives.refresh(),
+            Target::Sensor(_) => self.sensors.refresh(),
+            Target::Network(_) => self.networks.refresh(),
+            // Hwmon channels, swap devices and pressure are read anew for every command.
+            Target::Os | Target::SwapDevice(_) | Target::Pressure { .. } => {}
+        }
+    }
 
+    /// Creates the command which answers queries about the target from the information refreshed
+    /// last time.
+    fn build_command<'a>(
+        &'a mut self,
+        target: &Target,
+        options: &QueryOptions,
+    ) -> Result<Box<dyn Command + 'a>, DshwError> {
+        let data_unit = options.data_unit;
+
+        match target {
+            Target::Os => Ok(Box::new(OsCommand::new(self, options.time_format.clone()))),
+            Target::Cpu(name) => {
                 let cpu = self
                     .sys
                     .cpus()
                     .iter()
                     .find(|c| c.name() == name)
-                    .with_context(|| format!("cpu `{}` not found", name))?;
+                    .ok_or_else(|| DshwError::unknown_entity("cpu", name))?;
 
-                Ok((
-                    Box::new(CpuCommand::new(cpu)),
-                    queries.iter().map(|q| Query::Cpu(q.clone())).collect(),
-                ))
-            }
-            CliCommand::Memory { queries } => {
-                self.sys.refresh_memory();
+                let times = self.cpu_times.get(name).copied();
 
-                Ok((
-                    Box::new(MemoryCommand::new(self, cli.data_unit)),
-                    queries.iter().map(|q| Query::Memory(q.clone())).collect(),
-                ))
+                Ok(Box::new(CpuCommand::new(cpu, times, options.freq_unit)))
+            }
+            Target::Memory => Ok(Box::new(MemoryCommand::new(self, data_unit))),
+            Target::Swap => Ok(Box::new(SwapCommand::new(self, data_unit))),
+            Target::SwapDevice(name) => {
+                let device = swap_devices()
+                    .into_iter()
+                    .find(|d| d.name == *name)
+                    .ok_or_else(|| DshwError::unknown_entity("swap device", name))?;
+
+                Ok(Box::new(SwapDeviceCommand::new(device, data_unit)))
             }
-            CliCommand::Swap { queries } => {
-                self.sys.refresh_memory();
+            Target::Pressure { resource, cgroup } => {
+                let cgroup = cgroup.as_deref().map(cgroup_dir);
 
-                Ok((
-                    Box::new(SwapCommand::new(self, cli.data_unit)),
-                    queries.iter().map(|q| Query::Swap(q.clone())).collect(),
-                ))
+                if let Some(dir) = &cgroup {
+                    if !dir.is_dir() {
+                        return Err(DshwError::unknown_entity("cgroup", &dir.to_string_lossy()));
+                    }
+                }
+
+                let pressure = pressure(*resource, cgroup.as_deref());
+
+                Ok(Box::new(PressureCommand::new(pressure)))
             }
-            CliCommand::Drive { name, queries } => {
-                let drive = self
-                    .drives
-                    .list()
-                    .iter()
-                    .find(|d| d.name() == name.as_str())
-                    .with_context(|| format!("drive '{}' not found", name))?;
+            Target::Drive { name, by } => {
+                let drive = find_drive(self.drives.list(), name, *by)?;
 
-                Ok((
-                    Box::new(DriveCommand::new(drive, cli.data_unit)),
-                    queries.iter().map(|q| Query::Drive(q.clone())).collect(),
-                ))
+                Ok(Box::new(DriveCommand::new(drive, data_unit)))
             }
-            CliCommand::Sensor { name, queries } => {
-                let sensor = self
-                    .sensors
+            Target::Sensor(name) => {
+                if let Some(sensor) = self.sensors.iter().find(|c| c.label() == name) {
+                    return Ok(Box::new(SensorCommand::new(sensor)));
+                }
+
+                let channel = self
+                    .hwmon
                     .iter()
-                    .find(|c| c.label() == name)
-                    .with_context(|| format!("sensor '{}' not found", name))?;
+                    .find(|c| c.label == *name)
+                    .ok_or_else(|| DshwError::unknown_entity("sensor", name))?;
 
-                Ok((
-                    Box::new(SensorCommand::new(sensor)),
-                    queries.iter().map(|q| Query::Sensor(q.clone())).collect(),
-                ))
+                Ok(Box::new(HwmonSensorCommand::new(channel)))
             }
-            CliCommand::Network { name, queries } => {
+            Target::Network(name) => {
                 let network = self
                     .networks
                     .get(name)
-                    .with_context(|| format!("network `{}` not found", name))?;
+                    .ok_or_else(|| DshwError::unknown_entity("network", name))?;
 
-                Ok((
-                    Box::new(NetworkCommand::new(network, cli.data_unit)),
-                    queries.iter().map(|q| Query::Network(q.clone())).collect(),
-                ))
+                Ok(Box::new(NetworkCommand::new(
+                    name.clone(),
+                    network,
+                    data_unit,
+                )))
             }
-            CliCommand::ListSensors => Ok((Box::new(ListSensorsCommand::new(self)), vec![])),
-            CliCommand::ListCpus => Ok((Box::new(ListCpusCommand::new(self)), vec![])),
-            CliCommand::ListNetworks => Ok((Box::new(ListNetworksCommand::new(self)), vec![])),
         }
     }
 
+    /// Executes the queries against the target and returns their values.
+    pub fn query(
+        &mut self,
+        target: &Target,
+        queries: &[Query],
+        options: &QueryOptions,
+    ) -> Result<Vec<Value>, DshwError> {
+        if let Some(q) = queries.iter().find(|q| !q.is_for(target)) {
+            let reason = format!("cannot be issued against {:?}", target);
+
+            return Err(DshwError::invalid_query(&q.name(), reason));
+        }
+
+        let mut cmd = self.command(target, options)?;
+        let mut output = vec![];
+
+        for q in queries {
+            output.extend(cmd.exec(q.clone())?);
+        }
+
+        Ok(output)
+    }
+
+    /// Refreshes CPU usage and the times spent by CPUs in each state since the previous refresh
+    /// (e.g. the previous run with `-I`). The first refresh is measured over a short interval,
+    /// which is also the shortest interval used.
     pub fn refresh_cpus(&mut self) {
-        self.sys.refresh_cpu();
+        let (before, refreshed_at) = match self.cpu_stat.take() {
+            Some(stat) => stat,
+            None => {
+                self.sys.refresh_cpu();
+
+                (cpu_times(), Instant::now())
+            }
+        };
+
+        if let Some(rest) = sysinfo::MINIMUM_CPU_UPDATE_INTERVAL.checked_sub(refreshed_at.elapsed())
+        {
+            std::thread::sleep(rest);
+        }
 
-        std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
         self.sys.refresh_cpu();
+        let after = cpu_times();
+
+        self.cpu_times = match (&before, &after) {
+            (Some(before), Some(after)) => after
+                .iter()
+                .filter_map(|(name, t)| Some((name.clone(), t.since(before.get(name)?))))
+                .collect(),
+            _ => HashMap::new(),
+        };
+        self.cpu_stat = Some((after, Instant::now()));
     }
 
-    fn exec_cmd(&mut self, cli: &Cli) -> Result<()> {
-        let delimiter = unescape(&cli.delimiter)
-            .with_context(|| "invalid delimiter; are there any invalid escape sequences?")?;
+    /// Executes all queries of the command and returns their names and values. If no queries are
+    /// given, the command is executed with `Query::None` and the names are empty.
+    pub fn exec_queries(
+        &mut self,
+        cli_cmd: &InfoCommand,
+        data_unit: DataUnit,
+    ) -> Result<Vec<(String, Value)>> {
+        let mut output: Vec<(String, Value)> = vec![];
+
+        let (mut cmd, queries) = self.command_from_cli(cli_cmd, data_unit)?;
+
+        if !queries.is_empty() {
+            for q in queries {
+                let name = q.name();
+
+                output.extend(cmd.exec(q)?.into_iter().map(|v| (name.clone(), v)));
+            }
+        } else {
+            output.extend(
+                cmd.exec(Query::None)?
+                    .into_iter()
+                    .map(|v| (String::new(), v)),
+            );
+        }
+
+        Ok(output)
+    }
+
+    /// Executes the command, prints the output and returns the values that were printed along with
+    /// the names of their queries or specifiers.
+    fn exec_cmd(&mut self, cli: &Cli, cli_cmd: &InfoCommand) -> Result<Vec<(String, Value)>> {
+        let delimiter = unescape(&cli.delimiter).map_err(|_| {
+            DshwError::Template("invalid delimiter; are there any invalid escape sequences?".into())
+        })?;
+        let painter = Painter::new(cli.color, cli.color_rules.as_deref(), cli.data_unit)?;
+        let painter = painter.as_ref();
 
         if let Some(fmt) = &cli.fmt {
-            println!("{}", self.format_string(cli, fmt)?);
+            let (s, values) = if cli_cmd.target().is_none() {
+                self.format_items(cli, cli_cmd, fmt, painter, &delimiter)?
+            } else {
+                self.format_string(cli, cli_cmd, fmt, None, painter)?
+            };
+
+            println!("{}", s);
+
+            Ok(values)
         } else {
-            let data = cli.cmd.exec(cli)?;
+            let data = self.exec_queries(cli_cmd, cli.data_unit)?;
+            let data = self.smooth(&cli.smooth, data, cli.data_unit)?;
+
+            for (i, (_, d)) in data.iter().enumerate() {
+                let d = paint(painter, d);
 
-            for (i, d) in data.iter().enumerate() {
                 if i < data.len() - 1 {
                     print!("{}{}", d, delimiter)
                 } else {
                     println!("{}", d)
                 }
             }
+
+            Ok(data)
         }
+    }
 
-        Ok(())
+    /// Applies `--smooth` rules to the values. A rule for a specific query takes precedence over a
+    /// rule for all queries.
+    fn smooth(
+        &mut self,
+        rules: &[String],
+        values: Vec<(String, Value)>,
+        data_unit: DataUnit,
+    ) -> Result<Vec<(String, Value)>> {
+        if rules.is_empty() {
+            return Ok(values);
+        }
+
+        let rules: Vec<(Option<&str>, &str)> = rules
+            .iter()
+            .map(|r| match r.split_once('=') {
+                Some((q, filters)) => (Some(q), filters),
+                None => (None, r.as_str()),
+            })
+            .collect();
+
+        let mut output = vec![];
+
+        for (name, value) in values {
+            let rule = rules
+                .iter()
+                .find(|(q, _)| q.is_some_and(|q| q.eq_ignore_ascii_case(&name)))
+                .or_else(|| rules.iter().find(|(q, _)| q.is_none()));
+
+            let value = match rule {
+                Some((_, filters)) if !name.is_empty() => {
+                    let key = format!("{}={}", name, filters);
+
+                    self.filter(&key, filters, value, None, data_unit)?
+                }
+                _ => value,
+            };
+
+            output.push((name, value));
+        }
+
+        Ok(output)
+    }
+
+    /// Passes the value through the filters. The state of the filters is kept under the key.
+    fn filter(
+        &mut self,
+        key: &str,
+        filters: &str,
+        value: Value,
+        max: Option<f64>,
+        data_unit: DataUnit,
+    ) -> Result<Value, DshwError> {
+        let pipeline = match self.pipelines.entry(key.to_string()) {
+            Entry::Occupied(e) => e.into_mut(),
+            Entry::Vacant(e) => e.insert(Pipeline::parse(filters, data_unit)?),
+        };
+
+        pipeline.apply(value, max)
+    }
+
+    /// Executes all queries of the command and returns their names and values. All values are
+    /// guaranteed to be numeric.
+    fn exec_numeric(
+        &mut self,
+        cli_cmd: &InfoCommand,
+        data_unit: DataUnit,
+    ) -> Result<Vec<(String, Value)>> {
+        let (mut cmd, queries) = self.command_from_cli(cli_cmd, data_unit)?;
+
+        if queries.is_empty() {
+            return Err(DshwError::invalid_query("", "no queries given").into());
+        }
+
+        let mut results = vec![];
+
+        for q in queries {
+            let name = q.name();
+            let value = cmd.exec(q)?.into_iter().next().unwrap_or(Value::None);
+
+            match value {
+                Value::None => return Err(DshwError::Unavailable(name).into()),
+                Value::Text(_) => return Err(DshwError::invalid_query(&name, "not numeric").into()),
+                _ => {}
+            }
+
+            results.push((name, value));
+        }
+
+        Ok(results)
+    }
+
+    /// Executes the single query of a watched command and returns its name and numeric value.
+    fn exec_watched(
+        &mut self,
+        cli_cmd: &InfoCommand,
+        data_unit: DataUnit,
+    ) -> Result<(String, Value)> {
+        let mut results = self.exec_numeric(cli_cmd, data_unit)?;
+
+        if results.len() != 1 {
+            return Err(DshwError::invalid_query("", "`watch` requires exactly one query").into());
+        }
+
+        Ok(results.remove(0))
     }
 
-    fn format_string(&mut self, cli: &Cli, fmt: &str) -> Result<String> {
+    /// Runs the `check` command and prints its status line. Errors are reported as UNKNOWN.
+    fn check(&mut self, args: &CheckArgs, data_unit: DataUnit) -> ExitCode {
+        let report = Check::new(args, data_unit).and_then(|check| {
+            let results = self.exec_numeric(&args.cmd, data_unit)?;
+
+            Ok(check.report(&results))
+        });
+
+        let (status, line) =
+            report.unwrap_or_else(|e| (Status::Unknown, format!("DSHW UNKNOWN - {:#}", e)));
+
+        println!("{}", line);
+
+        status.exit_code()
+    }
+
+    /// Formats the string once for every item of the list command and joins the results by the
+    /// delimiter. The names of the values are prefixed by the names of the items (`cpu0/usage`).
+    fn format_items(
+        &mut self,
+        cli: &Cli,
+        cli_cmd: &InfoCommand,
+        fmt: &str,
+        painter: Option<&Painter>,
+        delimiter: &str,
+    ) -> Result<(String, Vec<(String, Value)>)> {
+        let names = match cli_cmd {
+            // Device names are not unique (`overlay`, `tmpfs`), so drives are named by their mount
+            // points.
+            InfoCommand::ListDrives { list } => {
+                ListDrivesCommand::new(self, list.clone(), true).exec(Query::None)?
+            }
+            _ => {
+                let (mut cmd, _) = self.command_from_cli(cli_cmd, cli.data_unit)?;
+
+                cmd.exec(Query::None)?
+            }
+        };
+
+        // All items are of the same kind, so the information about them is refreshed just once. This
+        // also makes CPU usages of all items sampled over the same interval.
+        if let Some((target, _)) = names
+            .first()
+            .and_then(|n| cli_cmd.item(&n.to_string()))
+            .and_then(|item| item.target())
+        {
+            self.refresh(&target);
+        }
+
+        let mut lines = vec![];
+        let mut values = vec![];
+
+        for name in names {
+            let name = name.to_string();
+            let item = cli_cmd.item(&name).expect("list commands have items");
+            let (s, v) = self.format_string(cli, &item, fmt, Some(&name), painter)?;
+
+            lines.push(s);
+            values.extend(v.into_iter().map(|(q, v)| (format!("{}/{}", name, q), v)));
+        }
+
+        Ok((lines.join(delimiter), values))
+    }
+
+    /// Replaces the specifiers in the format string by values, colored by the painter if any.
+    /// Returns the formatted string and the values of the specifiers. When formatting an item of a
+    /// list command, `%name%` is replaced by the name of the item.
+    fn format_string(
+        &mut self,
+        cli: &Cli,
+        cli_cmd: &InfoCommand,
+        fmt: &str,
+        item: Option<&str>,
+        painter: Option<&Painter>,
+    ) -> Result<(String, Vec<(String, Value)>)> {
         // Regex for parsing format specifiers %<SPECIFIER>%, or %% which yields just a percent sign.
         let re = Regex::new(r"\%(.*?)\%")?;
 
+        if re.replace_all(fmt, "").contains('%') {
+            return Err(DshwError::Template(
+                "invalid format string: unterminated format specifier".into(),
+            )
+            .into());
+        }
+
         let specs: Vec<String> = re
             .captures_iter(fmt)
             .map(|c| c.extract())
             .map(|(_, [r#match])| r#match.to_string())
             .collect();
 
-        let fmt_ctx = self.create_fmt_ctx(cli, specs)?;
+        // `%name%` of an item is not a query.
+        let is_name = |s: &str| item.is_some() && s.eq_ignore_ascii_case("name");
+        let (names, specs): (Vec<String>, Vec<String>) =
+            specs.into_iter().partition(|s| is_name(s));
 
-        Ok(re
-            .replace_all(fmt, |caps: &Captures| fmt_ctx.get(&caps[1]).unwrap())
-            .to_string())
-    }
-
-    fn create_fmt_ctx(&mut self, cli: &Cli, specs: Vec<String>) -> Result<FmtContext> {
-        let mut ctx: FmtContext = HashMap::new();
+        let values = self.exec_specs(cli, cli_cmd, specs, item)?;
 
+        let mut fmt_ctx: FmtContext = values
+            .iter()
+            .map(|(s, v)| (s.clone(), paint(painter, v)))
+            .collect();
         // Empty specifier (%% in regex input results in empty match) should be replaced as '%'.
-        ctx.insert(String::new(), "%".to_string());
-        // Remove all empty specifiers from input: we're gonna use specifier names to create command
-        // queries from them.
-        let specs: Vec<String> = specs.iter().filter(|s| !s.is_empty()).cloned().collect();
+        fmt_ctx.insert(String::new(), "%".to_string());
+
+        for s in names {
+            fmt_ctx.insert(s, item.unwrap_or_default().to_string());
+        }
+
+        // Every specifier has been put into the context, so the lookup cannot fail.
+        let s = re
+            .replace_all(fmt, |caps: &Captures| {
+                fmt_ctx.get(&caps[1]).map_or("", String::as_str).to_string()
+            })
+            .to_string();
+
+        Ok((s, values))
+    }
+
+    /// Executes the queries named by the specifiers and returns the values of unique specifiers in
+    /// the order they appear. The state of filters is kept separately for every item.
+    fn exec_specs(
+        &mut self,
+        cli: &Cli,
+        cli_cmd: &InfoCommand,
+        specs: Vec<String>,
+        item: Option<&str>,
+    ) -> Result<Vec<(String, Value)>> {
+        let mut values: Vec<(String, Value)> = vec![];
+
+        // Remove all empty and repeated specifiers from input: we're gonna use specifier names to
+        // create command queries from them.
+        let mut specs: Vec<String> = specs.into_iter().filter(|s| !s.is_empty()).collect();
+        let mut seen = HashSet::new();
+        specs.retain(|s| seen.insert(s.clone()));
 
         let mut queries: Vec<Query> = vec![];
 
+        // Filters follow the query name: `<QUERY>|<FILTER>|...`.
         for s in &specs {
-            queries.push(Query::from_str(&cli.cmd, s)?)
+            let name = s.split('|').next().unwrap_or_default();
+
+            queries.push(Query::from_str(cli_cmd, name)?)
         }
 
-        let (mut cmd, _) = self.command_from_cli(cli)?;
+        // Upper bounds of filtered values, used by bars and sparklines.
+        let mut bounds: Vec<Option<f64>> = vec![];
 
-        queries.into_iter().zip(specs).for_each(|(q, s)| {
-            ctx.insert(s.to_string(), cmd.exec(q).first().unwrap().to_string());
-        });
+        {
+            // Items are refreshed once for all of them by `format_items`.
+            let mut cmd = match (item, cli_cmd.target()) {
+                (Some(_), Some((target, _))) => {
+                    self.build_command(&target, &cli_cmd.options(cli.data_unit))?
+                }
+                _ => self.command_from_cli(cli_cmd, cli.data_unit)?.0,
+            };
+
+            for (q, s) in queries.into_iter().zip(specs) {
+                let bound = match q.bound() {
+                    Some(b) if s.contains('|') => cmd.exec(b)?.first().and_then(Value::as_f64),
+                    _ => None,
+                };
+                let value = cmd.exec(q)?.into_iter().next().unwrap_or(Value::None);
+
+                values.push((s, value));
+                bounds.push(bound);
+            }
+        }
+
+        for ((s, value), max) in values.iter_mut().zip(bounds) {
+            if let Some((_, filters)) = s.split_once('|') {
+                let v = std::mem::replace(value, Value::None);
+
+                let key = item.map_or_else(|| s.clone(), |i| format!("{}/{}", i, s));
+
+                *value = self.filter(&key, filters, v, max, cli.data_unit)?;
+            }
+        }
 
-        Ok(ctx)
+        Ok(values)
     }
 }
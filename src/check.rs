@@ -0,0 +1,180 @@
+// Copyright (c) 2024 inunix3
+//
+// This file is licensed under the MIT License (see LICENSE.md).
+
+use crate::{cli::CheckArgs, threshold::Threshold, units::DataUnit, value::Value};
+
+use anyhow::Result;
+
+use std::process::ExitCode;
+
+/// Service status as defined by the monitoring-plugins guidelines. The discriminant is the exit code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, strum_macros::Display)]
+pub enum Status {
+    #[strum(serialize = "OK")]
+    Ok = 0,
+    #[strum(serialize = "WARNING")]
+    Warning = 1,
+    #[strum(serialize = "CRITICAL")]
+    Critical = 2,
+    #[strum(serialize = "UNKNOWN")]
+    Unknown = 3,
+}
+
+impl Status {
+    pub fn exit_code(self) -> ExitCode {
+        ExitCode::from(self as u8)
+    }
+}
+
+#[derive(Debug)]
+pub struct Check {
+    warn: Option<Threshold>,
+    crit: Option<Threshold>,
+}
+
+impl Check {
+    pub fn new(args: &CheckArgs, data_unit: DataUnit) -> Result<Self> {
+        let warn = args
+            .warn
+            .as_deref()
+            .map(|w| Threshold::parse(w, data_unit))
+            .transpose()?;
+        let crit = args
+            .crit
+            .as_deref()
+            .map(|c| Threshold::parse(c, data_unit))
+            .transpose()?;
+
+        Ok(Self { warn, crit })
+    }
+
+    pub fn status(&self, value: f64) -> Status {
+        if self.crit.as_ref().is_some_and(|c| c.alerts(value)) {
+            Status::Critical
+        } else if self.warn.as_ref().is_some_and(|w| w.alerts(value)) {
+            Status::Warning
+        } else {
+            Status::Ok
+        }
+    }
+
+    /// Evaluates all query results and returns the worst status along with the plugin output line
+    /// (`DSHW OK - usage=42% | usage=42%;80;90`).
+    pub fn report(&self, results: &[(String, Value)]) -> (Status, String) {
+        let status = results
+            .iter()
+            .map(|(_, v)| v.as_f64().map_or(Status::Unknown, |v| self.status(v)))
+            .max()
+            .unwrap_or(Status::Unknown);
+        let text: Vec<String> = results
+            .iter()
+            .map(|(name, v)| format!("{}={}{}", name, v, unit_label(v)))
+            .collect();
+        let perfdata: Vec<String> = results
+            .iter()
+            .map(|(name, v)| self.perfdata(name, v))
+            .collect();
+
+        let line = format!(
+            "DSHW {} - {} | {}",
+            status,
+            text.join(", "),
+            perfdata.join(" ")
+        );
+
+        (status, line)
+    }
+
+    /// Formats the value as perfdata (`usage=42%;80;90`). Amounts of information are given in
+    /// bytes whatever the unit of the command, since the monitoring-plugins guidelines only know
+    /// B, KB, MB and TB; thresholds are converted along with them.
+    fn perfdata(&self, name: &str, value: &Value) -> String {
+        let (value, uom, factor) = match value {
+            Value::Data(d) => (format!("{:.0}", d.bytes()), "B", d.unit().factor()),
+            Value::Percent(_) => (value.to_string(), "%", 1.0),
+            _ => (value.to_string(), "", 1.0),
+        };
+        let threshold = |t: &Option<Threshold>| {
+            t.as_ref()
+                .map(|t| t.scaled(factor).to_string())
+                .unwrap_or_default()
+        };
+
+        format!(
+            "{}={}{};{};{}",
+            name,
+            value,
+            uom,
+            threshold(&self.warn),
+            threshold(&self.crit)
+        )
+    }
+}
+
+/// Unit of the value in the human-readable part of the output.
+fn unit_label(value: &Value) -> &'static str {
+    match value {
+        Value::Percent(_) => "%",
+        Value::Data(d) => match d.unit() {
+            DataUnit::Bits => "bit",
+            DataUnit::Bytes => "B",
+            DataUnit::Kb => "kB",
+            DataUnit::Kib => "KiB",
+            DataUnit::Mb => "MB",
+            DataUnit::Mib => "MiB",
+            DataUnit::Gb => "GB",
+            DataUnit::Gib => "GiB",
+            DataUnit::Tb => "TB",
+            DataUnit::Tib => "TiB",
+        },
+        _ => "",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::units::DataValue;
+
+    fn check(warn: &str, crit: &str, data_unit: DataUnit) -> Check {
+        Check {
+            warn: Some(Threshold::parse(warn, data_unit).unwrap()),
+            crit: Some(Threshold::parse(crit, data_unit).unwrap()),
+        }
+    }
+
+    #[test]
+    fn percent() {
+        let (status, line) = check("80", "90", DataUnit::Bytes)
+            .report(&[("usage-percent".to_string(), Value::Percent(85.0))]);
+
+        assert_eq!(status, Status::Warning);
+        assert_eq!(
+            line,
+            "DSHW WARNING - usage-percent=85.00% | usage-percent=85.00%;80;90"
+        );
+    }
+
+    #[test]
+    fn data_in_bytes() {
+        let usage = DataValue::from_bytes(3.0 * 1024.0 * 1024.0 * 1024.0, DataUnit::Gib);
+        let (status, line) =
+            check("2GiB", "4", DataUnit::Gib).report(&[("usage".to_string(), Value::Data(usage))]);
+
+        assert_eq!(status, Status::Warning);
+        assert_eq!(
+            line,
+            "DSHW WARNING - usage=3.00GiB | usage=3221225472B;2147483648;4294967296"
+        );
+    }
+
+    #[test]
+    fn missing_value() {
+        let (status, _) =
+            check("1", "2", DataUnit::Bytes).report(&[("temperature".to_string(), Value::None)]);
+
+        assert_eq!(status, Status::Unknown);
+    }
+}
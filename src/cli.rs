@@ -2,10 +2,18 @@
 //
 // This file is licensed under the MIT License (see LICENSE.md).
 
-use crate::{app::Application, query::*, units::DataUnit};
+use crate::{
+    color::ColorChoice,
+    completions::CompletionShell,
+    list::SortOrder,
+    query::*,
+    time::{TimeFormat, DEFAULT_TIME_FORMAT},
+    units::{DataUnit, FrequencyUnit},
+};
 
-use anyhow::Result;
-pub use clap::{Parser, Subcommand};
+use regex::Regex;
+
+pub use clap::{Args, Parser, Subcommand};
 
 /// Dead simple CLI program to query information about system and hardware.
 /// Basically a CLI wrapper over the sysinfo Rust crate.
@@ -19,10 +27,18 @@ pub struct Cli {
     /// Interval between commands, ignored if --run_times/-n is 1. For format see https://docs.rs/humantime/2.1.0/humantime/fn.parse_duration.html.
     #[arg(short = 'I', long)]
     pub interval: Option<humantime::Duration>,
+    /// Run commands on wall-clock multiples of the interval, e.g. on every full second with
+    /// `-I 1s` or on every full minute with `-I 1m`.
+    #[arg(long, requires = "interval")]
+    pub align: bool,
+    /// When the runs end (or on Ctrl-C), print min, max, mean, median, p95 and standard deviation
+    /// of every numeric query.
+    #[arg(long)]
+    pub summary: bool,
     /// How many times to run the command. Specifying 0 will cause commands to run infinitely until
-    /// the user manually terminates the program.
-    #[arg(short = 'n', long, default_value_t = 1, verbatim_doc_comment)]
-    pub run_times: u64,
+    /// the user manually terminates the program. Defaults to 1, or to 0 for `watch`.
+    #[arg(short = 'n', long, verbatim_doc_comment)]
+    pub run_times: Option<u64>,
     /// Delimiter used for separating responses. Also used by `list-cpus` and `list-sensors` commands.
     #[arg(short, long, default_value = "\n")]
     pub delimiter: String,
@@ -35,19 +51,67 @@ pub struct Cli {
     /// specifiers is `%<SPECIFIER>%`. To output the literal percent sign, write `%%`. If the specifier
     /// does not exist, a corresponding error is reported. Any supplied queries to the commands are
     /// ignored. The case does not matter (`%MAC-AddREss%` = `%mac-address%`).
+    /// Specifiers can be followed by filters separated by `|`: `avg:N` and `ema:DURATION` (see
+    /// `--smooth`), `bar:WIDTH[:MAX]` (a meter) and `spark:WIDTH[:MAX]` (a sparkline of the last
+    /// WIDTH values). The maximum is 100 for percentages and the total for memory, swap and drive
+    /// usage, otherwise it must be given (`%usage|bar:10%`, `%load-average1m|spark:20:4%`).
+    /// With list commands, the string is formatted for every item using its queries, `%name%` is
+    /// the name of the item (the mount point for drives, which are still filtered and sorted by
+    /// device name) and the results are separated by the delimiter
+    /// (`dshw -f '%name%: %temperature%' list-sensors`).
     #[arg(short, long, verbatim_doc_comment)]
     pub fmt: Option<String>,
+    /// Smooth numeric values across runs. The syntax is `[QUERY=]FILTER[|FILTER...]`; without a
+    /// query, the filters are applied to all queries. Filters are `avg:N` (moving average over
+    /// N samples) and `ema:DURATION` (exponential moving average with the time constant). In
+    /// format strings, filters are written after the specifier (`%usage|ema:5s%`).
+    #[arg(long, verbatim_doc_comment)]
+    pub smooth: Vec<String>,
+    /// When to color numeric values.
+    #[arg(long, default_value = "auto")]
+    pub color: ColorChoice,
+    /// Rules for coloring numeric values, separated by commas: `COLOR<BOUND`, `COLOR>BOUND` or just
+    /// `COLOR` for any value. The first matching rule applies. Bounds can have a unit suffix
+    /// (`red<1GiB,yellow<4GiB,green`). Without rules, only percentages are colored, by
+    /// `green<60,yellow<85,red`.
+    #[arg(long, verbatim_doc_comment)]
+    pub color_rules: Option<String>,
 }
 
 #[derive(Subcommand, Debug)]
 pub enum CliCommand {
+    #[command(flatten)]
+    Info(InfoCommand),
+    /// Watch a single query and run a command when its value crosses a threshold.
+    Watch(WatchArgs),
+    /// Check queries against thresholds like a monitoring plugin (Nagios, Icinga, Sensu). Prints
+    /// one status line with perfdata and exits with 0 (OK), 1 (WARNING), 2 (CRITICAL) or 3 (UNKNOWN).
+    #[command(verbatim_doc_comment)]
+    Check(CheckArgs),
+    /// Print a completion script for the shell. Names of CPUs, drives, sensors and networks are
+    /// completed from the running system.
+    Completions { shell: CompletionShell },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum InfoCommand {
     Os {
         queries: Vec<OsQuery>,
+        /// Pattern used by `boot-time-formatted`. For format see
+        /// https://docs.rs/chrono/latest/chrono/format/strftime/index.html.
+        #[arg(long, default_value = DEFAULT_TIME_FORMAT)]
+        time_format: String,
+        /// Format times in UTC instead of local time.
+        #[arg(long)]
+        utc: bool,
     },
     Cpu {
         #[clap(value_parser)]
         name: String,
         queries: Vec<CpuQuery>,
+        /// The unit of frequencies.
+        #[arg(long, value_enum, default_value_t)]
+        freq_unit: FrequencyUnit,
     },
     Memory {
         queries: Vec<MemoryQuery>,
@@ -55,8 +119,27 @@ pub enum CliCommand {
     Swap {
         queries: Vec<SwapQuery>,
     },
+    /// Query a single swap device or file.
+    SwapDevice {
+        /// The path of the device or file as listed by `list-swaps`.
+        name: String,
+        queries: Vec<SwapDeviceQuery>,
+    },
+    /// Query Pressure Stall Information (PSI) of a resource. Linux only.
+    Pressure {
+        resource: PressureResource,
+        /// Report the pressure of the cgroup instead of the whole system. The path may be given
+        /// with or without the `/sys/fs/cgroup` prefix (e.g. `/system.slice/sshd.service`).
+        #[arg(long)]
+        cgroup: Option<String>,
+        queries: Vec<PressureQuery>,
+    },
     Drive {
+        /// The drive, interpreted according to --by.
         name: String,
+        /// How the drive is selected.
+        #[arg(long, value_enum, default_value_t)]
+        by: DriveKey,
         queries: Vec<DriveQuery>,
     },
     Sensor {
@@ -68,28 +151,197 @@ pub enum CliCommand {
         queries: Vec<NetworkQuery>,
     },
     /// List all available sensors.
-    ListSensors,
+    ListSensors {
+        #[command(flatten)]
+        list: ListArgs,
+        /// Only list sensors of the chip (e.g. `coretemp`).
+        #[arg(long)]
+        chip: Option<String>,
+    },
     /// List all available CPUs.
-    ListCpus,
+    ListCpus {
+        #[command(flatten)]
+        list: ListArgs,
+        /// The unit of frequencies in the format string.
+        #[arg(long, value_enum, default_value_t)]
+        freq_unit: FrequencyUnit,
+    },
     /// List all available network interfaces.
-    ListNetworks,
+    ListNetworks {
+        #[command(flatten)]
+        list: ListArgs,
+        /// Only list interfaces backed by a device.
+        #[arg(long, conflicts_with = "virtual")]
+        physical: bool,
+        /// Only list virtual interfaces (loopback, bridges, tunnels, etc.).
+        #[arg(long = "virtual")]
+        r#virtual: bool,
+        /// Only list interfaces that are up.
+        #[arg(long)]
+        up: bool,
+    },
+    /// List all available drives.
+    ListDrives {
+        #[command(flatten)]
+        list: ListArgs,
+    },
+    /// List all active swap devices and files.
+    ListSwaps {
+        #[command(flatten)]
+        list: ListArgs,
+    },
+}
+
+/// Options shared by the list commands.
+#[derive(Args, Debug, Clone)]
+pub struct ListArgs {
+    /// Order of the names.
+    #[arg(long, value_enum, default_value_t)]
+    pub sort: SortOrder,
+    /// Only list names matching the regular expression.
+    #[arg(long, value_name = "REGEX")]
+    pub filter: Option<Regex>,
 }
 
-impl CliCommand {
-    pub fn exec(&self, cli: &Cli) -> Result<Vec<String>> {
-        let mut output: Vec<String> = vec![];
-        let mut app = Application::new();
+/// Arguments of the `watch` command. The options are global, so they can be given after the
+/// watched command (`dshw watch sensor 'coretemp Core 0' temperature --above 90 --exec ...`).
+#[derive(Args, Debug)]
+pub struct WatchArgs {
+    #[command(subcommand)]
+    pub cmd: InfoCommand,
+    /// The condition holds when the value is greater than this threshold.
+    #[arg(long, global = true, allow_negative_numbers = true)]
+    pub above: Option<f64>,
+    /// The condition holds when the value is less than this threshold.
+    #[arg(long, global = true, allow_negative_numbers = true)]
+    pub below: Option<f64>,
+    /// How long the condition must hold before the hook is run. Defaults to 0 (run immediately).
+    #[arg(long = "for", global = true, value_name = "DURATION")]
+    pub hold: Option<humantime::Duration>,
+    /// Shell command run once the condition has held for the given duration. The value, formatted
+    /// as dshw prints it, is passed in the `DSHW_VALUE` environment variable, the event (`trigger`
+    /// or `recover`) in `DSHW_EVENT` and the query in `DSHW_QUERY`.
+    #[arg(long, global = true, verbatim_doc_comment)]
+    pub exec: Option<String>,
+    /// Shell command run when the condition stops holding after the hook was triggered.
+    #[arg(long, global = true)]
+    pub recover: Option<String>,
+}
+
+/// Arguments of the `check` command. Thresholds use the monitoring-plugins range syntax
+/// (`10`, `10:`, `~:10`, `10:20`, `@10:20`) and the bounds can have a unit suffix (`90%`, `2GiB`).
+#[derive(Args, Debug)]
+pub struct CheckArgs {
+    #[command(subcommand)]
+    pub cmd: InfoCommand,
+    /// Warning threshold range.
+    #[arg(short, long, global = true, allow_hyphen_values = true)]
+    pub warn: Option<String>,
+    /// Critical threshold range.
+    #[arg(short, long, global = true, allow_hyphen_values = true)]
+    pub crit: Option<String>,
+}
+
+impl InfoCommand {
+    /// The target of the command along with its queries. List commands have no target.
+    pub fn target(&self) -> Option<(Target, Vec<Query>)> {
+        fn queries<Q: Clone + Into<Query>>(queries: &[Q]) -> Vec<Query> {
+            queries.iter().cloned().map(Into::into).collect()
+        }
 
-        let (mut cmd, queries) = app.command_from_cli(cli)?;
+        let target = match self {
+            Self::Os { queries: q, .. } => (Target::Os, queries(q)),
+            Self::Cpu {
+                name, queries: q, ..
+            } => (Target::Cpu(name.clone()), queries(q)),
+            Self::Memory { queries: q } => (Target::Memory, queries(q)),
+            Self::Swap { queries: q } => (Target::Swap, queries(q)),
+            Self::SwapDevice { name, queries: q } => (Target::SwapDevice(name.clone()), queries(q)),
+            Self::Pressure {
+                resource,
+                cgroup,
+                queries: q,
+            } => (
+                Target::Pressure {
+                    resource: *resource,
+                    cgroup: cgroup.clone(),
+                },
+                queries(q),
+            ),
+            Self::Drive {
+                name,
+                by,
+                queries: q,
+            } => (
+                Target::Drive {
+                    name: name.clone(),
+                    by: *by,
+                },
+                queries(q),
+            ),
+            Self::Sensor { name, queries: q } => (Target::Sensor(name.clone()), queries(q)),
+            Self::Network { name, queries: q } => (Target::Network(name.clone()), queries(q)),
+            Self::ListSensors { .. }
+            | Self::ListCpus { .. }
+            | Self::ListNetworks { .. }
+            | Self::ListDrives { .. }
+            | Self::ListSwaps { .. } => return None,
+        };
 
-        if !queries.is_empty() {
-            for q in queries {
-                output.extend(cmd.exec(q));
+        Some(target)
+    }
+
+    /// The options of the queries, given by the command line options of the command.
+    pub fn options(&self, data_unit: DataUnit) -> QueryOptions {
+        let mut options = QueryOptions::new(data_unit);
+
+        match self {
+            Self::Os {
+                time_format, utc, ..
+            } => {
+                options.time_format = TimeFormat {
+                    pattern: time_format.clone(),
+                    utc: *utc,
+                }
             }
-        } else {
-            output.extend(cmd.exec(Query::None));
+            Self::Cpu { freq_unit, .. } => options.freq_unit = *freq_unit,
+            _ => {}
         }
 
-        Ok(output)
+        options
+    }
+
+    /// The command answering queries about an item of a list command (e.g. `sensor <NAME>` for
+    /// `list-sensors`). Drives are selected by their mount points. Other commands have no items.
+    pub fn item(&self, name: &str) -> Option<InfoCommand> {
+        let name = name.to_string();
+
+        let cmd = match self {
+            Self::ListSensors { .. } => Self::Sensor {
+                name,
+                queries: vec![],
+            },
+            Self::ListCpus { freq_unit, .. } => Self::Cpu {
+                name,
+                queries: vec![],
+                freq_unit: *freq_unit,
+            },
+            Self::ListNetworks { .. } => Self::Network {
+                name,
+                queries: vec![],
+            },
+            Self::ListDrives { .. } => Self::Drive {
+                name,
+                by: DriveKey::Mount,
+                queries: vec![],
+            },
+            Self::ListSwaps { .. } => Self::SwapDevice {
+                name,
+                queries: vec![],
+            },
+            _ => return None,
+        };
+
+        Some(cmd)
     }
 }
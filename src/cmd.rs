@@ -4,131 +4,320 @@
 
 use crate::{
     app::Application,
+    cli::ListArgs,
+    cpufreq::CpuFreq,
+    cpustat::{CpuState, CpuTimes},
+    drive::{fs_stats, mount_options},
+    error::DshwError,
+    hwmon::{Channel, ChannelKind},
+    memory::{zram_used, MemInfo},
+    network::{addresses, is_physical, is_up, sysfs_attr},
+    pressure::Pressure,
+    process::{open_files, process_counts, sessions, ProcessCounts},
     query::*,
-    units::{DataUnit, DataValue},
+    swap::{swap_devices, zram_stats, SwapDevice, ZramStats},
+    time::{format_uptime, idle_time, TimeFormat},
+    units::{DataUnit, DataValue, FrequencyUnit},
+    value::Value,
 };
 
 use sysinfo::{Component, Cpu, Disk, NetworkData, System};
 
+use std::net::IpAddr;
+
 pub trait Command {
-    fn exec(&mut self, q: Query) -> Vec<String>;
+    fn exec(&mut self, q: Query) -> Result<Vec<Value>, DshwError>;
+}
+
+/// Time spent in the state as percentage, or nothing if the times are not available.
+fn cpu_time_value(times: Option<&CpuTimes>, state: CpuState) -> Value {
+    times
+        .and_then(|t| t.percent(state))
+        .map_or(Value::None, Value::Percent)
+}
+
+/// `part` as percentage of `whole`, or nothing if `whole` is 0.
+fn percent(part: f64, whole: f64) -> Value {
+    if whole > 0.0 {
+        Value::Percent(part / whole * 100.0)
+    } else {
+        Value::None
+    }
 }
 
 pub struct OsCommand<'a> {
     app: &'a mut Application,
+    /// Process counts shared by all queries of the command, read when first needed.
+    processes: Option<Option<ProcessCounts>>,
+    /// Whether CPU times have been refreshed for the queries of the command.
+    cpus_refreshed: bool,
+    time_format: TimeFormat,
 }
 
 impl Command for OsCommand<'_> {
-    fn exec(&mut self, q: Query) -> Vec<String> {
+    fn exec(&mut self, q: Query) -> Result<Vec<Value>, DshwError> {
         if let Query::None = q {
-            return vec![];
+            return Ok(vec![]);
         };
 
-        let s = if let Query::Os(q) = q {
-            match q {
-                OsQuery::BootTime => System::boot_time().to_string(),
-                OsQuery::LoadAverage1m => format!("{:.2}", System::load_average().one),
-                OsQuery::LoadAverage5m => format!("{:.2}", System::load_average().five),
-                OsQuery::LoadAverage15m => format!("{:.2}", System::load_average().fifteen),
-                OsQuery::Name => System::name().unwrap_or_default(),
-                OsQuery::KernelVersion => System::kernel_version().unwrap_or_default(),
-                OsQuery::Version => System::os_version().unwrap_or_default(),
-                OsQuery::LongVersion => System::long_os_version().unwrap_or_default(),
-                OsQuery::ReleaseId => System::distribution_id(),
-                OsQuery::HostName => System::host_name().unwrap_or_default(),
-                OsQuery::PhysicalCoreCount => {
-                    let count = self
-                        .app
-                        .sys
-                        .physical_core_count()
-                        .map(|c| c.to_string())
-                        .unwrap_or_default();
-
-                    count.to_string()
+        let s = match q {
+            Query::Os(q) => match q {
+                OsQuery::BootTime => Value::Integer(System::boot_time()),
+                OsQuery::LoadAverage1m => Value::Float(System::load_average().one),
+                OsQuery::LoadAverage5m => Value::Float(System::load_average().five),
+                OsQuery::LoadAverage15m => Value::Float(System::load_average().fifteen),
+                OsQuery::LoadAverage1mPerCpu => self.load_per_cpu(System::load_average().one),
+                OsQuery::LoadAverage5mPerCpu => self.load_per_cpu(System::load_average().five),
+                OsQuery::LoadAverage15mPerCpu => self.load_per_cpu(System::load_average().fifteen),
+                OsQuery::LoadAverage1mPerCore => self.load_per_core(System::load_average().one),
+                OsQuery::LoadAverage5mPerCore => self.load_per_core(System::load_average().five),
+                OsQuery::LoadAverage15mPerCore => {
+                    self.load_per_core(System::load_average().fifteen)
                 }
+                OsQuery::Name => System::name().into(),
+                OsQuery::KernelVersion => System::kernel_version().into(),
+                OsQuery::Version => System::os_version().into(),
+                OsQuery::LongVersion => System::long_os_version().into(),
+                OsQuery::ReleaseId => System::distribution_id().into(),
+                OsQuery::HostName => System::host_name().into(),
+                OsQuery::PhysicalCoreCount => self
+                    .app
+                    .sys
+                    .physical_core_count()
+                    .map_or(Value::None, |c| Value::Integer(c as u64)),
                 OsQuery::TotalCpuUsage => {
-                    self.app.refresh_cpus();
+                    self.refresh_cpus();
 
-                    format!("{:.2}", self.app.sys.global_cpu_info().cpu_usage())
+                    Value::Percent(self.app.sys.global_cpu_info().cpu_usage() as f64)
                 }
-                OsQuery::CpuArch => System::cpu_arch().unwrap_or_default(),
-            }
-        } else {
-            unreachable!()
+                OsQuery::CpuArch => System::cpu_arch().into(),
+                OsQuery::Uptime => Value::Integer(System::uptime()),
+                OsQuery::UptimeHuman => format_uptime(System::uptime()).into(),
+                OsQuery::IdleTime => idle_time().map_or(Value::None, Value::Float),
+                OsQuery::BootTimeFormatted => self.time_format.format(System::boot_time())?.into(),
+                OsQuery::Processes => self.process_count(|c| c.total),
+                OsQuery::RunningProcesses => self.process_count(|c| c.running),
+                OsQuery::SleepingProcesses => self.process_count(|c| c.sleeping),
+                OsQuery::ZombieProcesses => self.process_count(|c| c.zombie),
+                OsQuery::Threads => self.process_count(|c| c.threads),
+                OsQuery::OpenFiles => open_files().map_or(Value::None, |(n, _)| Value::Integer(n)),
+                OsQuery::MaxOpenFiles => {
+                    open_files().map_or(Value::None, |(_, max)| Value::Integer(max))
+                }
+                OsQuery::OpenFilesPercent => {
+                    open_files().map_or(Value::None, |(n, max)| percent(n as f64, max as f64))
+                }
+                OsQuery::Sessions => sessions().map_or(Value::None, Value::Integer),
+                OsQuery::CpuUser => self.cpu_time(CpuState::User),
+                OsQuery::CpuNice => self.cpu_time(CpuState::Nice),
+                OsQuery::CpuSystem => self.cpu_time(CpuState::System),
+                OsQuery::CpuIdle => self.cpu_time(CpuState::Idle),
+                OsQuery::CpuIowait => self.cpu_time(CpuState::Iowait),
+                OsQuery::CpuIrq => self.cpu_time(CpuState::Irq),
+                OsQuery::CpuSoftirq => self.cpu_time(CpuState::Softirq),
+                OsQuery::CpuSteal => self.cpu_time(CpuState::Steal),
+                OsQuery::CpuGuest => self.cpu_time(CpuState::Guest),
+            },
+            q => return Err(DshwError::unexpected_query(&q, "os")),
         };
 
-        vec![s]
+        Ok(vec![s])
     }
 }
 
 impl<'a> OsCommand<'a> {
-    pub fn new(app: &'a mut Application) -> Self {
-        Self { app }
+    pub fn new(app: &'a mut Application, time_format: TimeFormat) -> Self {
+        Self {
+            app,
+            processes: None,
+            cpus_refreshed: false,
+            time_format,
+        }
+    }
+
+    /// The load average divided by the number of logical CPUs.
+    fn load_per_cpu(&mut self, load: f64) -> Value {
+        // Only the number of CPUs is needed, so a single refresh is enough.
+        if self.app.sys.cpus().is_empty() {
+            self.app.sys.refresh_cpu();
+        }
+
+        match self.app.sys.cpus().len() {
+            0 => Value::None,
+            n => Value::Float(load / n as f64),
+        }
+    }
+
+    /// The load average divided by the number of physical cores.
+    fn load_per_core(&self, load: f64) -> Value {
+        match self.app.sys.physical_core_count() {
+            None | Some(0) => Value::None,
+            Some(n) => Value::Float(load / n as f64),
+        }
+    }
+
+    /// Refreshes CPUs once for all queries of the command, so that CPU usage and times are sampled
+    /// over the same interval.
+    fn refresh_cpus(&mut self) {
+        if !self.cpus_refreshed {
+            self.app.refresh_cpus();
+            self.cpus_refreshed = true;
+        }
+    }
+
+    /// Time all CPUs spent in the state as percentage.
+    fn cpu_time(&mut self, state: CpuState) -> Value {
+        self.refresh_cpus();
+
+        cpu_time_value(self.app.cpu_times.get("cpu"), state)
+    }
+
+    fn process_count<F: Fn(&ProcessCounts) -> u64>(&mut self, f: F) -> Value {
+        self.processes
+            .get_or_insert_with(process_counts)
+            .as_ref()
+            .map_or(Value::None, |c| Value::Integer(f(c)))
     }
 }
 
 pub struct CpuCommand<'a> {
     cpu: &'a Cpu,
+    /// Times spent in each state during the last refresh.
+    times: Option<CpuTimes>,
+    freq: CpuFreq,
+    freq_unit: FrequencyUnit,
 }
 
 impl Command for CpuCommand<'_> {
-    fn exec(&mut self, q: Query) -> Vec<String> {
+    fn exec(&mut self, q: Query) -> Result<Vec<Value>, DshwError> {
         if let Query::None = q {
-            return vec![];
+            return Ok(vec![]);
         };
 
-        let s = if let Query::Cpu(q) = q {
-            match q {
-                CpuQuery::Usage => format!("{:.2}", self.cpu.cpu_usage()),
-                CpuQuery::Frequency => self.cpu.frequency().to_string(),
-                CpuQuery::Brand => self.cpu.brand().to_string(),
-                CpuQuery::VendorId => self.cpu.vendor_id().to_string(),
-            }
-        } else {
-            unreachable!()
+        let s = match q {
+            Query::Cpu(q) => match q {
+                CpuQuery::Usage => Value::Percent(self.cpu.cpu_usage() as f64),
+                CpuQuery::Frequency => self.frequency(Some(self.cpu.frequency() * 1000)),
+                CpuQuery::Brand => self.cpu.brand().to_string().into(),
+                CpuQuery::VendorId => self.cpu.vendor_id().to_string().into(),
+                CpuQuery::MinFrequency => self.frequency(self.freq.khz("scaling_min_freq")),
+                CpuQuery::MaxFrequency => self.frequency(self.freq.khz("scaling_max_freq")),
+                CpuQuery::HardwareMinFrequency => self.frequency(self.freq.khz("cpuinfo_min_freq")),
+                CpuQuery::HardwareMaxFrequency => self.frequency(self.freq.khz("cpuinfo_max_freq")),
+                CpuQuery::BaseFrequency => self.frequency(self.freq.khz("base_frequency")),
+                CpuQuery::Governor => self.freq.attr("scaling_governor").into(),
+                CpuQuery::AvailableGovernors => {
+                    self.freq.attr("scaling_available_governors").into()
+                }
+                CpuQuery::Epp => self.freq.attr("energy_performance_preference").into(),
+                CpuQuery::Boost => self.freq.boost().map_or(Value::None, Value::Bool),
+                CpuQuery::User => cpu_time_value(self.times.as_ref(), CpuState::User),
+                CpuQuery::Nice => cpu_time_value(self.times.as_ref(), CpuState::Nice),
+                CpuQuery::System => cpu_time_value(self.times.as_ref(), CpuState::System),
+                CpuQuery::Idle => cpu_time_value(self.times.as_ref(), CpuState::Idle),
+                CpuQuery::Iowait => cpu_time_value(self.times.as_ref(), CpuState::Iowait),
+                CpuQuery::Irq => cpu_time_value(self.times.as_ref(), CpuState::Irq),
+                CpuQuery::Softirq => cpu_time_value(self.times.as_ref(), CpuState::Softirq),
+                CpuQuery::Steal => cpu_time_value(self.times.as_ref(), CpuState::Steal),
+                CpuQuery::Guest => cpu_time_value(self.times.as_ref(), CpuState::Guest),
+            },
+            q => return Err(DshwError::unexpected_query(&q, "cpu")),
         };
 
-        vec![s]
+        Ok(vec![s])
     }
 }
 
 impl<'a> CpuCommand<'a> {
-    pub fn new(cpu: &'a Cpu) -> Self {
-        Self { cpu }
+    pub fn new(cpu: &'a Cpu, times: Option<CpuTimes>, freq_unit: FrequencyUnit) -> Self {
+        Self {
+            cpu,
+            times,
+            freq: CpuFreq::new(cpu.name()),
+            freq_unit,
+        }
+    }
+
+    fn frequency(&self, khz: Option<u64>) -> Value {
+        match (khz, self.freq_unit) {
+            (None, _) => Value::None,
+            (Some(khz), FrequencyUnit::Mhz) => Value::Integer((khz + 500) / 1000),
+            (Some(khz), FrequencyUnit::Ghz) => Value::Float(khz as f64 / 1e6),
+        }
     }
 }
 
 pub struct MemoryCommand<'a> {
     app: &'a mut Application,
     data_unit: DataUnit,
+    meminfo: Option<Option<MemInfo>>,
 }
 
 impl Command for MemoryCommand<'_> {
-    fn exec(&mut self, q: Query) -> Vec<String> {
+    fn exec(&mut self, q: Query) -> Result<Vec<Value>, DshwError> {
         if let Query::None = q {
-            return vec![];
+            return Ok(vec![]);
         };
 
-        let value = if let Query::Memory(q) = q {
-            match q {
-                MemoryQuery::Usage => self.app.sys.used_memory() as f64,
-                MemoryQuery::Total => self.app.sys.total_memory() as f64,
-                MemoryQuery::Available => self.app.sys.available_memory() as f64,
-                MemoryQuery::Free => self.app.sys.free_memory() as f64,
-            }
-        } else {
-            unreachable!()
+        let sys = &self.app.sys;
+        let (total, used, available) = (
+            sys.total_memory(),
+            sys.used_memory(),
+            sys.available_memory(),
+        );
+
+        let value = match q {
+            Query::Memory(q) => match q {
+                MemoryQuery::Usage => self.data(Some(used)),
+                MemoryQuery::Total => self.data(Some(total)),
+                MemoryQuery::Available => self.data(Some(available)),
+                MemoryQuery::Free => self.data(Some(sys.free_memory())),
+                MemoryQuery::UsagePercent => percent(used as f64, total as f64),
+                MemoryQuery::AvailablePercent => percent(available as f64, total as f64),
+                MemoryQuery::Buffers => self.meminfo(|m| m.get("Buffers")),
+                MemoryQuery::Cached => self.meminfo(|m| m.get("Cached")),
+                MemoryQuery::Shared => self.meminfo(|m| m.get("Shmem")),
+                MemoryQuery::Slab => self.meminfo(|m| m.get("Slab")),
+                MemoryQuery::Dirty => self.meminfo(|m| m.get("Dirty")),
+                MemoryQuery::Writeback => self.meminfo(|m| m.get("Writeback")),
+                MemoryQuery::CommittedAs => self.meminfo(|m| m.get("Committed_AS")),
+                MemoryQuery::CommitLimit => self.meminfo(|m| m.get("CommitLimit")),
+                MemoryQuery::HugepagesTotal => self.meminfo(|m| m.huge_pages("HugePages_Total")),
+                MemoryQuery::HugepagesFree => self.meminfo(|m| m.huge_pages("HugePages_Free")),
+                MemoryQuery::Zswap => self.meminfo(|m| m.get("Zswap")),
+                MemoryQuery::Zram => self.data(zram_used()),
+            },
+            q => return Err(DshwError::unexpected_query(&q, "memory")),
         };
 
-        let value = DataValue::from_bytes(value, self.data_unit).value_str();
-
-        vec![value]
+        Ok(vec![value])
     }
 }
 
 impl<'a> MemoryCommand<'a> {
     pub fn new(app: &'a mut Application, data_unit: DataUnit) -> Self {
-        Self { app, data_unit }
+        Self {
+            app,
+            data_unit,
+            meminfo: None,
+        }
+    }
+
+    /// A field of `/proc/meminfo`, which is read once for all queries of the command.
+    fn meminfo<F: Fn(&MemInfo) -> Option<u64>>(&mut self, f: F) -> Value {
+        let bytes = self
+            .meminfo
+            .get_or_insert_with(MemInfo::read)
+            .as_ref()
+            .and_then(f);
+
+        self.data(bytes)
+    }
+
+    fn data(&self, bytes: Option<u64>) -> Value {
+        bytes.map_or(Value::None, |b| {
+            DataValue::from_bytes(b as f64, self.data_unit).into()
+        })
     }
 }
 
@@ -138,24 +327,26 @@ pub struct SwapCommand<'a> {
 }
 
 impl Command for SwapCommand<'_> {
-    fn exec(&mut self, q: Query) -> Vec<String> {
+    fn exec(&mut self, q: Query) -> Result<Vec<Value>, DshwError> {
         if let Query::None = q {
-            return vec![];
+            return Ok(vec![]);
         };
 
-        let value = if let Query::Swap(q) = q {
-            match q {
-                SwapQuery::Usage => self.app.sys.used_swap() as f64,
-                SwapQuery::Total => self.app.sys.total_swap() as f64,
-                SwapQuery::Available => self.app.sys.free_swap() as f64,
-            }
-        } else {
-            unreachable!()
+        let sys = &self.app.sys;
+        let (total, used, available) = (sys.total_swap(), sys.used_swap(), sys.free_swap());
+
+        let value = match q {
+            Query::Swap(q) => match q {
+                SwapQuery::Usage => self.data(used),
+                SwapQuery::Total => self.data(total),
+                SwapQuery::Available => self.data(available),
+                SwapQuery::UsagePercent => percent(used as f64, total as f64),
+                SwapQuery::AvailablePercent => percent(available as f64, total as f64),
+            },
+            q => return Err(DshwError::unexpected_query(&q, "swap")),
         };
 
-        let value = DataValue::from_bytes(value, self.data_unit).value_str();
-
-        vec![value]
+        Ok(vec![value])
     }
 }
 
@@ -163,6 +354,119 @@ impl<'a> SwapCommand<'a> {
     pub fn new(app: &'a mut Application, data_unit: DataUnit) -> Self {
         Self { app, data_unit }
     }
+
+    fn data(&self, bytes: u64) -> Value {
+        DataValue::from_bytes(bytes as f64, self.data_unit).into()
+    }
+}
+
+pub struct SwapDeviceCommand {
+    device: SwapDevice,
+    data_unit: DataUnit,
+    /// zram statistics shared by all queries of the command, read when first needed.
+    zram: Option<Option<ZramStats>>,
+}
+
+impl Command for SwapDeviceCommand {
+    fn exec(&mut self, q: Query) -> Result<Vec<Value>, DshwError> {
+        if let Query::None = q {
+            return Ok(vec![]);
+        };
+
+        let s = match q {
+            Query::SwapDevice(q) => match q {
+                SwapDeviceQuery::Type => self.device.kind.clone().into(),
+                SwapDeviceQuery::Priority => Value::SignedInteger(self.device.priority),
+                SwapDeviceQuery::Size => self.data(Some(self.device.size)),
+                SwapDeviceQuery::Used => self.data(Some(self.device.used)),
+                SwapDeviceQuery::UsagePercent => {
+                    percent(self.device.used as f64, self.device.size as f64)
+                }
+                SwapDeviceQuery::CompressionRatio => self
+                    .zram()
+                    .and_then(ZramStats::ratio)
+                    .map_or(Value::None, Value::Float),
+                SwapDeviceQuery::OriginalSize => {
+                    let original = self.zram().map(|z| z.original);
+
+                    self.data(original)
+                }
+                SwapDeviceQuery::CompressedSize => {
+                    let compressed = self.zram().map(|z| z.compressed);
+
+                    self.data(compressed)
+                }
+                SwapDeviceQuery::MemUsed => {
+                    let mem_used = self.zram().map(|z| z.mem_used);
+
+                    self.data(mem_used)
+                }
+                SwapDeviceQuery::Algorithm => self.zram().and_then(|z| z.algorithm.clone()).into(),
+            },
+            q => return Err(DshwError::unexpected_query(&q, "swap device")),
+        };
+
+        Ok(vec![s])
+    }
+}
+
+impl SwapDeviceCommand {
+    pub fn new(device: SwapDevice, data_unit: DataUnit) -> Self {
+        Self {
+            device,
+            data_unit,
+            zram: None,
+        }
+    }
+
+    fn zram(&mut self) -> Option<&ZramStats> {
+        let name = &self.device.name;
+
+        self.zram.get_or_insert_with(|| zram_stats(name)).as_ref()
+    }
+
+    fn data(&self, bytes: Option<u64>) -> Value {
+        bytes.map_or(Value::None, |b| {
+            DataValue::from_bytes(b as f64, self.data_unit).into()
+        })
+    }
+}
+
+pub struct PressureCommand {
+    pressure: Option<Pressure>,
+}
+
+impl Command for PressureCommand {
+    fn exec(&mut self, q: Query) -> Result<Vec<Value>, DshwError> {
+        if let Query::None = q {
+            return Ok(vec![]);
+        };
+
+        let some = self.pressure.and_then(|p| p.some);
+        let full = self.pressure.and_then(|p| p.full);
+
+        let s = match q {
+            Query::Pressure(q) => match q {
+                PressureQuery::SomeAvg10 => some.map_or(Value::None, |s| Value::Percent(s.avg10)),
+                PressureQuery::SomeAvg60 => some.map_or(Value::None, |s| Value::Percent(s.avg60)),
+                PressureQuery::SomeAvg300 => some.map_or(Value::None, |s| Value::Percent(s.avg300)),
+                PressureQuery::SomeTotal => some.map_or(Value::None, |s| Value::Integer(s.total)),
+                PressureQuery::FullAvg10 => full.map_or(Value::None, |s| Value::Percent(s.avg10)),
+                PressureQuery::FullAvg60 => full.map_or(Value::None, |s| Value::Percent(s.avg60)),
+                PressureQuery::FullAvg300 => full.map_or(Value::None, |s| Value::Percent(s.avg300)),
+                PressureQuery::FullTotal => full.map_or(Value::None, |s| Value::Integer(s.total)),
+            },
+            q => return Err(DshwError::unexpected_query(&q, "pressure")),
+        };
+
+        Ok(vec![s])
+    }
+}
+
+impl PressureCommand {
+    pub fn new(pressure: Option<Pressure>) -> Self {
+        Self { pressure }
+    }
 }
 
 pub struct DriveCommand<'a> {
@@ -171,36 +475,58 @@ pub struct DriveCommand<'a> {
 }
 
 impl Command for DriveCommand<'_> {
-    fn exec(&mut self, q: Query) -> Vec<String> {
+    fn exec(&mut self, q: Query) -> Result<Vec<Value>, DshwError> {
         if let Query::None = q {
-            return vec![];
+            return Ok(vec![]);
         };
 
         let total_space = self.drive.total_space();
         let avail_space = self.drive.available_space();
         let used_space = total_space - avail_space;
 
-        let s = if let Query::Drive(q) = q {
-            match q {
+        let s = match q {
+            Query::Drive(q) => match q {
                 DriveQuery::Usage => {
-                    DataValue::from_bytes(used_space as f64, self.data_unit).value_str()
+                    DataValue::from_bytes(used_space as f64, self.data_unit).into()
                 }
-                DriveQuery::Fs => self.drive.file_system().to_string_lossy().to_string(),
-                DriveQuery::IsRemovable => (self.drive.is_removable() as i32).to_string(),
-                DriveQuery::Kind => self.drive.kind().to_string(),
-                DriveQuery::MountPoint => self.drive.mount_point().to_string_lossy().to_string(),
+                DriveQuery::Fs => self
+                    .drive
+                    .file_system()
+                    .to_string_lossy()
+                    .to_string()
+                    .into(),
+                DriveQuery::IsRemovable => Value::Bool(self.drive.is_removable()),
+                DriveQuery::Kind => self.drive.kind().to_string().into(),
+                DriveQuery::MountPoint => self
+                    .drive
+                    .mount_point()
+                    .to_string_lossy()
+                    .to_string()
+                    .into(),
                 DriveQuery::Total => {
-                    DataValue::from_bytes(total_space as f64, self.data_unit).value_str()
+                    DataValue::from_bytes(total_space as f64, self.data_unit).into()
                 }
                 DriveQuery::Available => {
-                    DataValue::from_bytes(avail_space as f64, self.data_unit).value_str()
+                    DataValue::from_bytes(avail_space as f64, self.data_unit).into()
                 }
-            }
-        } else {
-            unreachable!()
+                DriveQuery::UsagePercent => percent(used_space as f64, total_space as f64),
+                DriveQuery::AvailablePercent => percent(avail_space as f64, total_space as f64),
+                DriveQuery::Inodes => fs_stats(self.drive.mount_point())
+                    .map_or(Value::None, |st| Value::Integer(st.inodes)),
+                DriveQuery::FreeInodes => fs_stats(self.drive.mount_point())
+                    .map_or(Value::None, |st| Value::Integer(st.free_inodes)),
+                DriveQuery::InodeUsagePercent => fs_stats(self.drive.mount_point())
+                    .map_or(Value::None, |st| {
+                        percent((st.inodes - st.free_inodes) as f64, st.inodes as f64)
+                    }),
+                DriveQuery::IsReadOnly => fs_stats(self.drive.mount_point())
+                    .map_or(Value::None, |st| Value::Bool(st.read_only)),
+                DriveQuery::MountOptions => mount_options(self.drive.mount_point()).into(),
+            },
+            q => return Err(DshwError::unexpected_query(&q, "drive")),
         };
 
-        vec![s]
+        Ok(vec![s])
     }
 }
 
@@ -215,26 +541,30 @@ pub struct SensorCommand<'a> {
 }
 
 impl Command for SensorCommand<'_> {
-    fn exec(&mut self, q: Query) -> Vec<String> {
+    fn exec(&mut self, q: Query) -> Result<Vec<Value>, DshwError> {
         if let Query::None = q {
-            return vec![];
+            return Ok(vec![]);
         };
 
-        let s = if let Query::Sensor(q) = q {
-            match q {
+        let s = match q {
+            Query::Sensor(q) => match q {
                 SensorQuery::CriticalTemp => self
                     .sensor
                     .critical()
-                    .map(|t| format!("{:.2}", t))
-                    .unwrap_or_default(),
-                SensorQuery::MaxTemp => format!("{:.2}", self.sensor.max()),
-                SensorQuery::Temperature => format!("{:.2}", self.sensor.temperature()),
-            }
-        } else {
-            unreachable!()
+                    .map_or(Value::None, |t| Value::Float(t as f64)),
+                SensorQuery::MaxTemp => Value::Float(self.sensor.max() as f64),
+                SensorQuery::Temperature => Value::Float(self.sensor.temperature() as f64),
+                SensorQuery::Reading => Value::Float(self.sensor.temperature() as f64),
+                // Limits are not available for temperature sensors; `max-temp` is the highest
+                // temperature recorded, not a limit.
+                SensorQuery::Min | SensorQuery::Max | SensorQuery::Alarm => Value::None,
+                SensorQuery::Kind => "temperature".to_string().into(),
+                SensorQuery::Unit => "°C".to_string().into(),
+            },
+            q => return Err(DshwError::unexpected_query(&q, "sensor")),
         };
 
-        vec![s]
+        Ok(vec![s])
     }
 }
 
@@ -244,121 +574,284 @@ impl<'a> SensorCommand<'a> {
     }
 }
 
+pub struct HwmonSensorCommand<'a> {
+    channel: &'a Channel,
+}
+
+impl Command for HwmonSensorCommand<'_> {
+    fn exec(&mut self, q: Query) -> Result<Vec<Value>, DshwError> {
+        if let Query::None = q {
+            return Ok(vec![]);
+        };
+
+        let s = match q {
+            Query::Sensor(q) => match q {
+                SensorQuery::CriticalTemp | SensorQuery::MaxTemp | SensorQuery::Temperature => {
+                    Value::None
+                }
+                SensorQuery::Reading => self.reading(self.channel.input()),
+                SensorQuery::Min => self.reading(self.channel.min()),
+                SensorQuery::Max => self.reading(self.channel.max()),
+                SensorQuery::Alarm => self.channel.alarm().map_or(Value::None, Value::Bool),
+                SensorQuery::Kind => self.channel.kind.to_string().into(),
+                SensorQuery::Unit => self.channel.kind.unit().to_string().into(),
+            },
+            q => return Err(DshwError::unexpected_query(&q, "sensor")),
+        };
+
+        Ok(vec![s])
+    }
+}
+
+impl<'a> HwmonSensorCommand<'a> {
+    pub fn new(channel: &'a Channel) -> Self {
+        Self { channel }
+    }
+
+    /// Fan speeds are whole numbers, other readings have 2 decimal places.
+    fn reading(&self, v: Option<f64>) -> Value {
+        match (v, self.channel.kind) {
+            (None, _) => Value::None,
+            (Some(v), ChannelKind::Fan) => Value::Integer(v as u64),
+            (Some(v), _) => Value::Float(v),
+        }
+    }
+}
+
 pub struct NetworkCommand<'a> {
+    name: String,
     network: &'a NetworkData,
     data_unit: DataUnit,
 }
 
 impl Command for NetworkCommand<'_> {
-    fn exec(&mut self, q: Query) -> Vec<String> {
+    fn exec(&mut self, q: Query) -> Result<Vec<Value>, DshwError> {
         if let Query::None = q {
-            return vec![];
+            return Ok(vec![]);
         };
 
-        let s = if let Query::Network(q) = q {
-            match q {
-                NetworkQuery::MacAddress => self.network.mac_address().to_string(),
+        let s = match q {
+            Query::Network(q) => match q {
+                NetworkQuery::MacAddress => self.network.mac_address().to_string().into(),
                 NetworkQuery::TotalIncomingErrors => {
-                    self.network.total_errors_on_received().to_string()
+                    Value::Integer(self.network.total_errors_on_received())
                 }
                 NetworkQuery::TotalOutcomingErrors => {
-                    self.network.total_errors_on_transmitted().to_string()
+                    Value::Integer(self.network.total_errors_on_transmitted())
                 }
                 NetworkQuery::TotalReceivedData => {
                     let received = self.network.total_received();
 
-                    DataValue::from_bytes(received as f64, self.data_unit).value_str()
+                    DataValue::from_bytes(received as f64, self.data_unit).into()
                 }
                 NetworkQuery::TotalTransmittedData => {
                     let transmitted = self.network.total_transmitted();
 
-                    DataValue::from_bytes(transmitted as f64, self.data_unit).value_str()
+                    DataValue::from_bytes(transmitted as f64, self.data_unit).into()
                 }
                 NetworkQuery::TotalReceivedPackets => {
-                    self.network.total_packets_received().to_string()
+                    Value::Integer(self.network.total_packets_received())
                 }
                 NetworkQuery::TotalTransmittedPackets => {
-                    self.network.total_packets_transmitted().to_string()
+                    Value::Integer(self.network.total_packets_transmitted())
                 }
-            }
-        } else {
-            unreachable!()
+                NetworkQuery::Ipv4 => self.addresses(|ip| ip.is_ipv4()),
+                NetworkQuery::Ipv6 => self.addresses(|ip| ip.is_ipv6()),
+                NetworkQuery::Mtu => self.attr("mtu").map_or(Value::None, Value::Integer),
+                NetworkQuery::Operstate => sysfs_attr(&self.name, "operstate").into(),
+                NetworkQuery::Carrier => self
+                    .attr("carrier")
+                    .map_or(Value::None, |c| Value::Bool(c != 0)),
+                NetworkQuery::Speed => self.attr("speed").map_or(Value::None, Value::Integer),
+                NetworkQuery::Duplex => sysfs_attr(&self.name, "duplex").into(),
+            },
+            q => return Err(DshwError::unexpected_query(&q, "network")),
         };
 
-        vec![s]
+        Ok(vec![s])
     }
 }
 
 impl<'a> NetworkCommand<'a> {
-    pub fn new(network: &'a NetworkData, data_unit: DataUnit) -> Self {
-        Self { network, data_unit }
+    pub fn new(name: String, network: &'a NetworkData, data_unit: DataUnit) -> Self {
+        Self {
+            name,
+            network,
+            data_unit,
+        }
+    }
+
+    /// A numeric sysfs attribute. Unknown values like the speed of -1 are treated as missing.
+    fn attr(&self, attr: &str) -> Option<u64> {
+        sysfs_attr(&self.name, attr)?.parse().ok()
+    }
+
+    /// Space-separated addresses of the interface that satisfy the predicate, or nothing if none
+    /// does.
+    fn addresses<F: Fn(&IpAddr) -> bool>(&self, pred: F) -> Value {
+        let addrs: Vec<String> = addresses(&self.name)
+            .into_iter()
+            .filter(|(ip, _)| pred(ip))
+            .map(|(ip, prefix)| format!("{}/{}", ip, prefix))
+            .collect();
+
+        if addrs.is_empty() {
+            Value::None
+        } else {
+            Value::Text(addrs.join(" "))
+        }
     }
 }
 
 pub struct ListCpusCommand<'a> {
     app: &'a mut Application,
+    list: ListArgs,
 }
 
 impl Command for ListCpusCommand<'_> {
-    fn exec(&mut self, _q: Query) -> Vec<String> {
-        let mut output: Vec<String> = vec![];
-
+    fn exec(&mut self, _q: Query) -> Result<Vec<Value>, DshwError> {
         self.app.sys.refresh_cpu();
 
-        for c in self.app.sys.cpus() {
-            output.push(c.name().to_string())
-        }
+        let names = self
+            .app
+            .sys
+            .cpus()
+            .iter()
+            .map(|c| c.name().to_string())
+            .collect();
 
-        output
+        Ok(list_values(self.list.arrange(names)))
     }
 }
 
 impl<'a> ListCpusCommand<'a> {
-    pub fn new(app: &'a mut Application) -> Self {
-        Self { app }
+    pub fn new(app: &'a mut Application, list: ListArgs) -> Self {
+        Self { app, list }
     }
 }
 
 pub struct ListSensorsCommand<'a> {
     app: &'a mut Application,
+    list: ListArgs,
+    chip: Option<String>,
 }
 
 impl Command for ListSensorsCommand<'_> {
-    fn exec(&mut self, _q: Query) -> Vec<String> {
-        let mut output: Vec<String> = vec![];
-
-        for c in &*self.app.sensors {
-            output.push(c.label().to_string())
-        }
-
-        output
+    fn exec(&mut self, _q: Query) -> Result<Vec<Value>, DshwError> {
+        // Labels start with the name of the chip (`coretemp Core 0`).
+        let names = self
+            .app
+            .sensors
+            .iter()
+            .map(|c| c.label().to_string())
+            .chain(self.app.hwmon.iter().map(|c| c.label.clone()))
+            .filter(
+                |l| !matches!(&self.chip, Some(chip) if l.split(' ').next() != Some(chip.as_str())),
+            )
+            .collect();
+
+        Ok(list_values(self.list.arrange(names)))
     }
 }
 
 impl<'a> ListSensorsCommand<'a> {
-    pub fn new(app: &'a mut Application) -> Self {
-        Self { app }
+    pub fn new(app: &'a mut Application, list: ListArgs, chip: Option<String>) -> Self {
+        Self { app, list, chip }
     }
 }
 
 pub struct ListNetworksCommand<'a> {
     app: &'a mut Application,
+    list: ListArgs,
+    /// Only list physical (`Some(true)`) or virtual (`Some(false)`) interfaces.
+    physical: Option<bool>,
+    up: bool,
 }
 
 impl Command for ListNetworksCommand<'_> {
-    fn exec(&mut self, _q: Query) -> Vec<String> {
-        let mut output: Vec<String> = vec![];
+    fn exec(&mut self, _q: Query) -> Result<Vec<Value>, DshwError> {
+        let names = self
+            .app
+            .networks
+            .keys()
+            .map(|name| name.to_string())
+            .filter(|name| !matches!(self.physical, Some(p) if is_physical(name) != p))
+            .filter(|name| !self.up || is_up(name))
+            .collect();
+
+        Ok(list_values(self.list.arrange(names)))
+    }
+}
 
-        for (interface_name, _) in &*self.app.networks {
-            output.push(interface_name.to_string())
+impl<'a> ListNetworksCommand<'a> {
+    pub fn new(app: &'a mut Application, list: ListArgs, physical: Option<bool>, up: bool) -> Self {
+        Self {
+            app,
+            list,
+            physical,
+            up,
         }
+    }
+}
+
+pub struct ListDrivesCommand<'a> {
+    app: &'a mut Application,
+    list: ListArgs,
+    /// List mount points instead of device names. Drives are still filtered and sorted by their
+    /// device names.
+    mounts: bool,
+}
 
-        output
+impl Command for ListDrivesCommand<'_> {
+    fn exec(&mut self, _q: Query) -> Result<Vec<Value>, DshwError> {
+        let drives: Vec<(String, String)> = self
+            .app
+            .drives
+            .list()
+            .iter()
+            .map(|d| {
+                (
+                    d.name().to_string_lossy().to_string(),
+                    d.mount_point().to_string_lossy().to_string(),
+                )
+            })
+            .collect();
+
+        let names = self
+            .list
+            .arrange_by(drives, |(name, _)| name)
+            .into_iter()
+            .map(|(name, mount)| if self.mounts { mount } else { name })
+            .collect();
+
+        Ok(list_values(names))
     }
 }
 
-impl<'a> ListNetworksCommand<'a> {
-    pub fn new(app: &'a mut Application) -> Self {
-        Self { app }
+impl<'a> ListDrivesCommand<'a> {
+    pub fn new(app: &'a mut Application, list: ListArgs, mounts: bool) -> Self {
+        Self { app, list, mounts }
     }
 }
+
+pub struct ListSwapsCommand {
+    list: ListArgs,
+}
+
+impl Command for ListSwapsCommand {
+    fn exec(&mut self, _q: Query) -> Result<Vec<Value>, DshwError> {
+        let names = swap_devices().into_iter().map(|d| d.name).collect();
+
+        Ok(list_values(self.list.arrange(names)))
+    }
+}
+
+impl ListSwapsCommand {
+    pub fn new(list: ListArgs) -> Self {
+        Self { list }
+    }
+}
+
+fn list_values(names: Vec<String>) -> Vec<Value> {
+    names.into_iter().map(Value::Text).collect()
+}
@@ -0,0 +1,139 @@
+// Copyright (c) 2024 inunix3
+//
+// This file is licensed under the MIT License (see LICENSE.md).
+
+use crate::{error::DshwError, threshold::parse_bound, units::DataUnit, value::Value};
+
+use clap::ValueEnum;
+
+use std::io::{self, IsTerminal};
+
+/// Rules used for percentages if colors are enabled and no rules are given.
+pub const DEFAULT_COLOR_RULES: &str = "green<60,yellow<85,red";
+
+#[derive(Debug, ValueEnum, Clone, Copy)]
+pub enum ColorChoice {
+    /// Color the output if it goes to a terminal and `NO_COLOR` is not set.
+    Auto,
+    Always,
+    Never,
+}
+
+#[derive(Debug, ValueEnum, Clone, Copy)]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+}
+
+impl Color {
+    fn ansi_code(&self) -> u8 {
+        30 + *self as u8
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Condition {
+    Always,
+    Below(f64),
+    Above(f64),
+}
+
+/// Colors numeric values according to the first matching rule.
+#[derive(Debug)]
+pub struct Painter {
+    rules: Vec<(Color, Condition)>,
+    /// Whether the rules are the default ones, which only make sense for percentages.
+    default_rules: bool,
+}
+
+impl Painter {
+    /// Returns `None` if the output should not be colored. Rules are separated by commas and have
+    /// the form `COLOR<BOUND`, `COLOR>BOUND` or `COLOR` (matches any value). Bounds can have a
+    /// unit suffix (`red<1GiB`). Without rules, only percentages are colored.
+    pub fn new(
+        choice: ColorChoice,
+        rules: Option<&str>,
+        data_unit: DataUnit,
+    ) -> Result<Option<Self>, DshwError> {
+        let enabled = match choice {
+            ColorChoice::Auto => {
+                io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none()
+            }
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+        };
+
+        if !enabled {
+            return Ok(None);
+        }
+
+        let default_rules = rules.is_none();
+        let rules = rules
+            .unwrap_or(DEFAULT_COLOR_RULES)
+            .split(',')
+            .map(|r| parse_rule(r.trim(), data_unit))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Some(Self {
+            rules,
+            default_rules,
+        }))
+    }
+
+    /// Formats the value wrapped in the color of the matching rule. Text, missing values and flags
+    /// are not colored, and neither are values other than percentages with the default rules.
+    pub fn paint(&self, value: &Value) -> String {
+        let v = match value {
+            Value::None | Value::Text(_) | Value::Bool(_) => None,
+            Value::Percent(_) => value.as_f64(),
+            _ if self.default_rules => None,
+            _ => value.as_f64(),
+        };
+
+        let color = v.and_then(|v| {
+            self.rules.iter().find_map(|(color, cond)| match cond {
+                Condition::Always => Some(color),
+                Condition::Below(b) if v < *b => Some(color),
+                Condition::Above(b) if v > *b => Some(color),
+                _ => None,
+            })
+        });
+
+        match color {
+            Some(c) => format!("\x1b[{}m{}\x1b[0m", c.ansi_code(), value),
+            None => value.to_string(),
+        }
+    }
+}
+
+/// Formats the value, colored if there is a painter.
+pub fn paint(painter: Option<&Painter>, value: &Value) -> String {
+    painter.map_or_else(|| value.to_string(), |p| p.paint(value))
+}
+
+fn parse_rule(s: &str, data_unit: DataUnit) -> Result<(Color, Condition), DshwError> {
+    let (color, cond) = match s.find(['<', '>']) {
+        Some(i) => {
+            let bound = parse_bound(&s[i + 1..], data_unit)?;
+            let cond = if s[i..].starts_with('<') {
+                Condition::Below(bound)
+            } else {
+                Condition::Above(bound)
+            };
+
+            (&s[..i], cond)
+        }
+        None => (s, Condition::Always),
+    };
+
+    let color = Color::from_str(color, true)
+        .map_err(|_| DshwError::Template(format!("invalid color `{}` in rule `{}`", color, s)))?;
+
+    Ok((color, cond))
+}
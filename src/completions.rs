@@ -0,0 +1,245 @@
+// Copyright (c) 2024 inunix3
+//
+// This file is licensed under the MIT License (see LICENSE.md).
+
+use crate::cli::Cli;
+
+use clap::{CommandFactory, ValueEnum};
+use clap_complete::{generate, Shell};
+use regex::Regex;
+
+#[derive(Debug, ValueEnum, Clone, Copy)]
+pub enum CompletionShell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+/// Bash functions which complete the `name` argument of `cpu`, `drive`, `sensor`, `network` and
+/// `swap-device` (also after `watch` and `check`) by calling the corresponding `list-*` command,
+/// and defer everything else to the generated `_dshw`. `@VALUE_OPTIONS@` is replaced by the
+/// options which take a value, so that their values are not mistaken for subcommands.
+const BASH_NAMES: &str = r#"
+# Prints the subcommand if the current word is its first argument.
+_dshw_subcommand() {
+    local i word skip=0
+    local -a positional=()
+
+    for (( i = 1; i < COMP_CWORD; i++ )); do
+        word="${COMP_WORDS[i]}"
+
+        if (( skip )); then
+            skip=0
+            continue
+        fi
+
+        case "${word}" in
+            @VALUE_OPTIONS@) skip=1 ;;
+            # `--opt=value` is split into three words.
+            =) skip=1 ;;
+            -*) ;;
+            *) positional+=("${word}") ;;
+        esac
+    done
+
+    case "${positional[0]}" in
+        watch|check) positional=("${positional[@]:1}") ;;
+    esac
+
+    (( ${#positional[@]} == 1 )) && echo "${positional[0]}"
+}
+
+_dshw_names() {
+    local cur="${COMP_WORDS[COMP_CWORD]}" list
+    case "$(_dshw_subcommand)" in
+        cpu) list=list-cpus ;;
+        drive) list=list-drives ;;
+        sensor) list=list-sensors ;;
+        network) list=list-networks ;;
+        swap-device) list=list-swaps ;;
+        *)
+            _dshw "$@"
+            return
+            ;;
+    esac
+
+    local IFS=$'\n'
+    COMPREPLY=( $(compgen -W "$(dshw "${list}" 2>/dev/null)" -- "${cur}") )
+    COMPREPLY=( $(printf '%q\n' "${COMPREPLY[@]}") )
+}
+
+complete -F _dshw_names -o bashdefault -o default dshw
+"#;
+
+/// Zsh function used as the action of the `name` argument. `words[1]` is the subcommand here.
+const ZSH_NAMES: &str = r#"
+_dshw_names() {
+    local list
+    case $words[1] in
+        (cpu) list=list-cpus ;;
+        (drive) list=list-drives ;;
+        (sensor) list=list-sensors ;;
+        (network) list=list-networks ;;
+        (swap-device) list=list-swaps ;;
+        (*) return 1 ;;
+    esac
+
+    local -a names
+    names=("${(@f)$(dshw $list 2>/dev/null)}")
+    compadd -a names
+}
+"#;
+
+/// Fish functions which complete the `name` argument like [`BASH_NAMES`].
+const FISH_NAMES: &str = r#"
+# Prints the subcommand if the current token is its first argument.
+function __fish_dshw_subcommand
+    set -l tokens (commandline -opc)
+    set -l positional
+    set -l skip 0
+
+    set -e tokens[1]
+    for token in $tokens
+        if test $skip -eq 1
+            set skip 0
+        else if contains -- $token @VALUE_OPTIONS@
+            set skip 1
+        else if not string match -q -- '-*' $token
+            set -a positional $token
+        end
+    end
+
+    if contains -- "$positional[1]" watch check
+        set -e positional[1]
+    end
+
+    test (count $positional) -eq 1; and echo $positional[1]
+end
+
+function __fish_dshw_names
+    switch (__fish_dshw_subcommand)
+        case cpu
+            dshw list-cpus 2>/dev/null
+        case drive
+            dshw list-drives 2>/dev/null
+        case sensor
+            dshw list-sensors 2>/dev/null
+        case network
+            dshw list-networks 2>/dev/null
+        case swap-device
+            dshw list-swaps 2>/dev/null
+    end
+end
+
+function __fish_dshw_needs_name
+    set -l subcommand (__fish_dshw_subcommand); or return 1
+    contains -- $subcommand cpu drive sensor network swap-device
+end
+
+complete -c dshw -n "__fish_dshw_needs_name" -f -a "(__fish_dshw_names)"
+"#;
+
+/// Options of all commands which take a value (`-u`, `--data-unit`, `--fmt`, etc.).
+fn value_options(cmd: &clap::Command) -> Vec<String> {
+    let mut options: Vec<String> = cmd
+        .get_arguments()
+        .filter(|a| !a.is_positional() && a.get_action().takes_values())
+        .flat_map(|a| {
+            let short = a.get_short().map(|s| format!("-{}", s));
+            let long = a.get_long().map(|l| format!("--{}", l));
+
+            short.into_iter().chain(long)
+        })
+        .chain(cmd.get_subcommands().flat_map(value_options))
+        .collect();
+
+    options.sort();
+    options.dedup();
+    options
+}
+
+/// Generates a completion script for the shell. Names of CPUs, drives, sensors, networks and swap
+/// devices are completed at completion time from the live system.
+pub fn generate_script(shell: CompletionShell) -> String {
+    let mut buf: Vec<u8> = vec![];
+    let mut cmd = Cli::command();
+    let bin_name = cmd.get_name().to_string();
+
+    let clap_shell = match shell {
+        CompletionShell::Bash => Shell::Bash,
+        CompletionShell::Zsh => Shell::Zsh,
+        CompletionShell::Fish => Shell::Fish,
+    };
+
+    generate(clap_shell, &mut cmd, bin_name, &mut buf);
+
+    let script = String::from_utf8_lossy(&buf).into_owned();
+
+    match shell {
+        CompletionShell::Bash => {
+            script + &BASH_NAMES.replace("@VALUE_OPTIONS@", &value_options(&cmd).join("|"))
+        }
+        CompletionShell::Zsh => {
+            // The `name` argument may have help text (`':name -- The drive...:_default'`), which
+            // is kept. The function must be defined before the generated script invokes `_dshw`,
+            // so it goes right after the `#compdef` line.
+            let name_action = Regex::new(r"'(:name(?: -- [^':]*)?):_default'").unwrap();
+            let script = name_action.replace_all(&script, "'${1}:_dshw_names'");
+            let (compdef, rest) = script.split_once('\n').unwrap_or((&script, ""));
+
+            format!("{}\n{}{}", compdef, ZSH_NAMES, rest)
+        }
+        CompletionShell::Fish => {
+            script + &FISH_NAMES.replace("@VALUE_OPTIONS@", &value_options(&cmd).join(" "))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::process::Command;
+
+    /// Completes the words with the generated bash script, where `dshw` itself only lists `cpu0`.
+    fn complete_bash(words: &[&str]) -> Vec<String> {
+        let script = format!(
+            r#"{}
+dshw() {{ echo cpu0; }}
+COMP_WORDS=({})
+COMP_CWORD={}
+_dshw_names dshw "${{COMP_WORDS[COMP_CWORD]}}" "${{COMP_WORDS[COMP_CWORD-1]}}"
+printf '%s\n' "${{COMPREPLY[@]}}"
+"#,
+            generate_script(CompletionShell::Bash),
+            words
+                .iter()
+                .map(|w| format!("'{}'", w))
+                .collect::<Vec<_>>()
+                .join(" "),
+            words.len() - 1,
+        );
+
+        let output = Command::new("bash").arg("-c").arg(script).output().unwrap();
+
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(str::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn bash_names() {
+        assert_eq!(complete_bash(&["dshw", "cpu", ""]), ["cpu0"]);
+        assert_eq!(complete_bash(&["dshw", "-u", "gib", "cpu", ""]), ["cpu0"]);
+        assert_eq!(complete_bash(&["dshw", "watch", "cpu", ""]), ["cpu0"]);
+    }
+
+    #[test]
+    fn bash_pressure_resource_is_not_a_name() {
+        let words = complete_bash(&["dshw", "pressure", "cpu", ""]);
+
+        assert!(words.iter().any(|w| w == "some-avg10"));
+        assert!(!words.iter().any(|w| w == "cpu0"));
+    }
+}
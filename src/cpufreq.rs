@@ -0,0 +1,53 @@
+// Copyright (c) 2024 inunix3
+//
+// This file is licensed under the MIT License (see LICENSE.md).
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+const CPU_DIR: &str = "/sys/devices/system/cpu";
+
+/// Frequency scaling information of a CPU from `/sys/devices/system/cpu/<CPU>/cpufreq`.
+#[derive(Debug, Clone)]
+pub struct CpuFreq {
+    dir: PathBuf,
+}
+
+impl CpuFreq {
+    /// `cpu` is the name of the CPU as reported by sysinfo (`cpu0`).
+    pub fn new(cpu: &str) -> Self {
+        Self {
+            dir: Path::new(CPU_DIR).join(cpu).join("cpufreq"),
+        }
+    }
+
+    /// A frequency attribute (`scaling_max_freq`, `base_frequency`, etc.) in kHz.
+    pub fn khz(&self, attr: &str) -> Option<u64> {
+        self.attr(attr)?.parse().ok()
+    }
+
+    /// A textual attribute (`scaling_governor`, etc.).
+    pub fn attr(&self, attr: &str) -> Option<String> {
+        read(&self.dir.join(attr))
+    }
+
+    /// Determine if frequency boost (turbo) is enabled, either for this CPU or globally.
+    pub fn boost(&self) -> Option<bool> {
+        let cpu_dir = Path::new(CPU_DIR);
+
+        if let Some(boost) =
+            read(&self.dir.join("boost")).or_else(|| read(&cpu_dir.join("cpufreq/boost")))
+        {
+            return Some(boost == "1");
+        }
+
+        // intel_pstate reports the opposite.
+        read(&cpu_dir.join("intel_pstate/no_turbo")).map(|no_turbo| no_turbo == "0")
+    }
+}
+
+fn read(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok().map(|s| s.trim().to_string())
+}
@@ -0,0 +1,78 @@
+// Copyright (c) 2024 inunix3
+//
+// This file is licensed under the MIT License (see LICENSE.md).
+
+use std::{collections::HashMap, fs};
+
+/// A state the CPU spends time in, as accounted in `/proc/stat`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CpuState {
+    User,
+    Nice,
+    System,
+    Idle,
+    Iowait,
+    Irq,
+    Softirq,
+    Steal,
+    Guest,
+}
+
+/// Time spent by a CPU in each state (in clock ticks).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CpuTimes {
+    /// The values in the order of `/proc/stat`: user, nice, system, idle, iowait, irq, softirq,
+    /// steal, guest and guest_nice.
+    ticks: [u64; 10],
+}
+
+impl CpuTimes {
+    pub fn get(&self, state: CpuState) -> u64 {
+        self.ticks[state as usize]
+    }
+
+    /// Time spent in the state as percentage of all time. Returns nothing if no time has passed.
+    pub fn percent(&self, state: CpuState) -> Option<f64> {
+        // Guest time is already included in user and nice time.
+        let total: u64 = self.ticks[..8].iter().sum();
+
+        (total > 0).then(|| self.get(state) as f64 / total as f64 * 100.0)
+    }
+
+    /// Time spent since the earlier times. Counters that went backwards count as 0.
+    pub fn since(&self, earlier: &CpuTimes) -> CpuTimes {
+        let mut ticks = [0; 10];
+
+        for (i, t) in ticks.iter_mut().enumerate() {
+            *t = self.ticks[i].saturating_sub(earlier.ticks[i]);
+        }
+
+        CpuTimes { ticks }
+    }
+}
+
+/// Reads times of all CPUs from `/proc/stat`, keyed by the name of the CPU (`cpu0`). The sum over
+/// all CPUs is under `cpu`. Returns nothing if `/proc/stat` is not available.
+pub fn cpu_times() -> Option<HashMap<String, CpuTimes>> {
+    let stat = fs::read_to_string("/proc/stat").ok()?;
+
+    let times = stat
+        .lines()
+        .filter(|line| line.starts_with("cpu"))
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let name = fields.next()?.to_string();
+
+            // Older kernels report fewer fields.
+            let mut ticks = [0; 10];
+
+            for (t, f) in ticks.iter_mut().zip(fields) {
+                *t = f.parse().ok()?;
+            }
+
+            Some((name, CpuTimes { ticks }))
+        })
+        .collect();
+
+    Some(times)
+}
@@ -0,0 +1,232 @@
+// Copyright (c) 2024 inunix3
+//
+// This file is licensed under the MIT License (see LICENSE.md).
+
+use crate::{error::DshwError, query::DriveKey};
+
+use sysinfo::Disk;
+
+use std::{fs, path::Path};
+
+/// Filesystem statistics of a mount point.
+#[derive(Debug, Clone, Copy)]
+pub struct FsStats {
+    pub inodes: u64,
+    pub free_inodes: u64,
+    pub read_only: bool,
+}
+
+/// Gets the statistics of the filesystem mounted at the path with `statvfs`.
+#[cfg(unix)]
+pub fn fs_stats(mount_point: &Path) -> Option<FsStats> {
+    use std::{ffi::CString, mem::MaybeUninit, os::unix::ffi::OsStrExt};
+
+    let path = CString::new(mount_point.as_os_str().as_bytes()).ok()?;
+    let mut st = MaybeUninit::<libc::statvfs>::uninit();
+
+    // SAFETY: `path` is a valid C string and `st` is only read if the call succeeded.
+    let st = unsafe {
+        if libc::statvfs(path.as_ptr(), st.as_mut_ptr()) != 0 {
+            return None;
+        }
+
+        st.assume_init()
+    };
+
+    Some(FsStats {
+        #[allow(clippy::useless_conversion)]
+        inodes: st.f_files.into(),
+        #[allow(clippy::useless_conversion)]
+        free_inodes: st.f_ffree.into(),
+        read_only: st.f_flag & libc::ST_RDONLY != 0,
+    })
+}
+
+#[cfg(not(unix))]
+pub fn fs_stats(_mount_point: &Path) -> Option<FsStats> {
+    None
+}
+
+/// Mount options of the mount point from `/proc/self/mountinfo`: the options of the mount followed
+/// by the options of the filesystem that are not already present.
+pub fn mount_options(mount_point: &Path) -> Option<String> {
+    let mountinfo = fs::read_to_string("/proc/self/mountinfo").ok()?;
+
+    parse_mount_options(&mountinfo, mount_point)
+}
+
+/// Finds the mount options of the mount point in the contents of a mountinfo file.
+fn parse_mount_options(mountinfo: &str, mount_point: &Path) -> Option<String> {
+    // Format: `ID PARENT MAJ:MIN ROOT MOUNT-POINT OPTIONS [OPTIONAL...] - FS SOURCE SUPER-OPTIONS`.
+    // A later mount at the same mount point hides the earlier ones.
+    mountinfo.lines().rev().find_map(|line| {
+        let (mount, fs) = line.split_once(" - ")?;
+        let mount: Vec<&str> = mount.split(' ').collect();
+
+        if Path::new(&unescape_octal(mount.get(4)?)) != mount_point {
+            return None;
+        }
+
+        let mut options: Vec<&str> = mount.get(5)?.split(',').collect();
+
+        for opt in fs.split(' ').nth(2).unwrap_or_default().split(',') {
+            if !opt.is_empty() && !options.contains(&opt) {
+                options.push(opt);
+            }
+        }
+
+        Some(options.join(","))
+    })
+}
+
+/// Replaces octal escapes (`\040`) used by the kernel for spaces and other special characters.
+pub(crate) fn unescape_octal(s: &str) -> String {
+    let mut bytes = vec![];
+    let mut rest = s.as_bytes();
+
+    while let Some((&b, tail)) = rest.split_first() {
+        let code = tail
+            .get(..3)
+            .and_then(|d| std::str::from_utf8(d).ok())
+            .and_then(|d| u8::from_str_radix(d, 8).ok());
+
+        match code {
+            Some(c) if b == b'\\' => {
+                bytes.push(c);
+                rest = &tail[3..];
+            }
+            _ => {
+                bytes.push(b);
+                rest = tail;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+/// Finds the drive selected by the name interpreted according to the key. Fails if no drive or
+/// more than one drive matches.
+pub fn find_drive<'a>(drives: &'a [Disk], name: &str, by: DriveKey) -> Result<&'a Disk, DshwError> {
+    let matches: Vec<&Disk> = match by {
+        DriveKey::Name => drives.iter().filter(|d| d.name() == name).collect(),
+        DriveKey::Mount => drives
+            .iter()
+            .filter(|d| d.mount_point() == Path::new(name))
+            .collect(),
+        DriveKey::Label | DriveKey::Uuid => {
+            let dir = match by {
+                DriveKey::Label => "/dev/disk/by-label",
+                _ => "/dev/disk/by-uuid",
+            };
+
+            // The links point to the device node, which drives may name differently
+            // (`/dev/mapper/root` vs `/dev/dm-0`).
+            match fs::canonicalize(Path::new(dir).join(udev_escape(name))) {
+                Ok(dev) => drives
+                    .iter()
+                    .filter(|d| fs::canonicalize(d.name()).is_ok_and(|p| p == dev))
+                    .collect(),
+                Err(_) => vec![],
+            }
+        }
+        DriveKey::Path => {
+            let path =
+                fs::canonicalize(name).map_err(|_| DshwError::unknown_entity("path", name))?;
+            let containing: Vec<&Disk> = drives
+                .iter()
+                .filter(|d| path.starts_with(d.mount_point()))
+                .collect();
+            let depth = |d: &Disk| d.mount_point().components().count();
+            let deepest = containing.iter().map(|d| depth(d)).max();
+
+            containing
+                .into_iter()
+                .filter(|d| Some(depth(d)) == deepest)
+                .collect()
+        }
+    };
+
+    match matches.as_slice() {
+        [] => Err(DshwError::unknown_entity("drive", name)),
+        [drive] => Ok(drive),
+        _ => Err(DshwError::AmbiguousEntity {
+            kind: "drive",
+            name: name.to_string(),
+            candidates: matches
+                .iter()
+                .map(|d| {
+                    format!(
+                        "{} on {}",
+                        d.name().to_string_lossy(),
+                        d.mount_point().display()
+                    )
+                })
+                .collect(),
+        }),
+    }
+}
+
+/// Escapes the name like udev does for the links in `/dev/disk` (a space becomes `\x20`).
+fn udev_escape(name: &str) -> String {
+    let mut escaped = String::new();
+
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() || "#+-.:=@_".contains(c) || !c.is_ascii() {
+            escaped.push(c);
+        } else {
+            escaped.push_str(&format!("\\x{:02x}", c as u8));
+        }
+    }
+
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MOUNTINFO: &str = "\
+22 1 8:2 / / rw,relatime shared:1 - ext4 /dev/sda2 rw,errors=remount-ro
+40 22 8:3 / /mnt/my\\040disk rw,nosuid,relatime shared:20 - vfat /dev/sda3 rw,fmask=0022
+41 22 0:40 / /tmp rw,nosuid,nodev - tmpfs tmpfs rw,size=1024k
+42 22 0:41 / /tmp ro,relatime - tmpfs tmpfs ro,size=2048k
+";
+
+    fn options(mount_point: &str) -> Option<String> {
+        parse_mount_options(MOUNTINFO, Path::new(mount_point))
+    }
+
+    #[test]
+    fn merges_superblock_options() {
+        assert_eq!(
+            options("/").as_deref(),
+            Some("rw,relatime,errors=remount-ro")
+        );
+    }
+
+    #[test]
+    fn escaped_mount_point() {
+        assert_eq!(
+            options("/mnt/my disk").as_deref(),
+            Some("rw,nosuid,relatime,fmask=0022")
+        );
+        assert_eq!(options("/mnt/my\\040disk"), None);
+    }
+
+    #[test]
+    fn last_mount_wins() {
+        assert_eq!(options("/tmp").as_deref(), Some("ro,relatime,size=2048k"));
+        assert_eq!(options("/nonexistent"), None);
+    }
+
+    #[test]
+    fn unescape() {
+        assert_eq!(unescape_octal("/mnt/my\\040disk"), "/mnt/my disk");
+        assert_eq!(unescape_octal("a\\011b\\134c"), "a\tb\\c");
+        // Incomplete or non-octal escapes are kept as is.
+        assert_eq!(unescape_octal("a\\04"), "a\\04");
+        assert_eq!(unescape_octal("a\\089b"), "a\\089b");
+        assert_eq!(unescape_octal("plain"), "plain");
+    }
+}
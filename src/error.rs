@@ -0,0 +1,78 @@
+// Copyright (c) 2024 inunix3
+//
+// This file is licensed under the MIT License (see LICENSE.md).
+
+use crate::query::Query;
+
+use std::{io, process::ExitCode};
+
+#[derive(Debug, thiserror::Error)]
+pub enum DshwError {
+    /// No CPU, drive, sensor, network, etc. has the requested name.
+    #[error("{kind} `{name}` not found")]
+    UnknownEntity { kind: &'static str, name: String },
+    /// More than one entity matches the requested name.
+    #[error("{kind} `{name}` is ambiguous, it matches {}", .candidates.join(", "))]
+    AmbiguousEntity {
+        kind: &'static str,
+        name: String,
+        candidates: Vec<String>,
+    },
+    /// The query does not exist or cannot be used in the given context. The query is empty if the
+    /// queries as a whole are invalid (e.g. none were given).
+    #[error("invalid query{}: {reason}", quoted(.query))]
+    InvalidQuery { query: String, reason: String },
+    /// The query exists, but its value cannot be obtained on this system.
+    #[error("`{0}` is not available")]
+    Unavailable(String),
+    /// The format string, delimiter or another template of the output (time format, filter, color
+    /// rule, threshold) is malformed.
+    #[error("{0}")]
+    Template(String),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+/// The name in backticks preceded by a space, or nothing if it is empty.
+fn quoted(name: &str) -> String {
+    if name.is_empty() {
+        String::new()
+    } else {
+        format!(" `{}`", name)
+    }
+}
+
+impl DshwError {
+    pub fn unknown_entity(kind: &'static str, name: &str) -> Self {
+        Self::UnknownEntity {
+            kind,
+            name: name.to_string(),
+        }
+    }
+
+    pub fn invalid_query(query: &str, reason: impl Into<String>) -> Self {
+        Self::InvalidQuery {
+            query: query.to_string(),
+            reason: reason.into(),
+        }
+    }
+
+    /// The query was passed to a command it does not belong to.
+    pub fn unexpected_query(q: &Query, kind: &str) -> Self {
+        Self::invalid_query(&q.name(), format!("not a {} query", kind))
+    }
+
+    /// Exit code of the program when it fails with this error. 1 is used for all other errors and 2
+    /// for invalid command line usage.
+    pub fn exit_code(&self) -> ExitCode {
+        let code = match self {
+            Self::UnknownEntity { .. } | Self::AmbiguousEntity { .. } => 3,
+            Self::InvalidQuery { .. } => 4,
+            Self::Unavailable(_) => 5,
+            Self::Template(_) => 6,
+            Self::Io(_) => 7,
+        };
+
+        ExitCode::from(code)
+    }
+}
@@ -0,0 +1,292 @@
+// Copyright (c) 2024 inunix3
+//
+// This file is licensed under the MIT License (see LICENSE.md).
+
+use crate::{error::DshwError, threshold::parse_bound, units::DataUnit, value::Value};
+
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+/// Blocks used by bars, from 1/8 to a full block.
+const BAR_BLOCKS: [char; 8] = ['▏', '▎', '▍', '▌', '▋', '▊', '▉', '█'];
+/// Blocks used by sparklines, from the lowest to the highest.
+const SPARK_BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// A filter applied to the value of a query. In format strings, filters are written after the
+/// specifier and separated by `|` (`%usage|ema:5s%`).
+#[derive(Debug, Clone)]
+pub enum Filter {
+    /// Moving average over the last N samples (`avg:N`).
+    MovingAverage(usize),
+    /// Exponential moving average with a time constant (`ema:DURATION`).
+    Ema(Duration),
+    /// Meter of the given width (`bar:WIDTH[:MAX]`).
+    Bar { width: usize, max: Option<f64> },
+    /// Sparkline of the last WIDTH values (`spark:WIDTH[:MAX]`).
+    Spark { width: usize, max: Option<f64> },
+}
+
+impl Filter {
+    /// Parses a filter. The maximum of bars and sparklines may have a unit suffix, which is
+    /// converted to `data_unit`.
+    pub fn parse(s: &str, data_unit: DataUnit) -> Result<Self, DshwError> {
+        let invalid = || DshwError::Template(format!("invalid filter `{}`", s));
+
+        let mut args = s.split(':');
+        let name = args.next().unwrap_or_default().to_lowercase();
+        let arg = args.next().unwrap_or_default();
+        let max = args
+            .next()
+            .map(|m| parse_bound(m, data_unit))
+            .transpose()
+            .map_err(|_| invalid())?;
+
+        if args.next().is_some() {
+            return Err(invalid());
+        }
+
+        let width = || match arg.parse() {
+            Ok(n) if n > 0 => Ok(n),
+            _ => Err(invalid()),
+        };
+
+        match (name.as_str(), max) {
+            ("avg", None) => Ok(Self::MovingAverage(width()?)),
+            ("ema", None) => humantime::parse_duration(arg)
+                .map(Self::Ema)
+                .map_err(|_| invalid()),
+            ("bar", _) => Ok(Self::Bar {
+                width: width()?,
+                max,
+            }),
+            ("spark", _) => Ok(Self::Spark {
+                width: width()?,
+                max,
+            }),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// A filter along with the state it keeps across runs.
+#[derive(Debug)]
+struct Stage {
+    filter: Filter,
+    samples: VecDeque<f64>,
+    /// The last output and when it was produced.
+    last: Option<(f64, Instant)>,
+}
+
+impl Stage {
+    /// `max` is the known upper bound of the value, if any.
+    fn apply(&mut self, value: Value, max: Option<f64>, now: Instant) -> Result<Value, DshwError> {
+        let Some(v) = value.as_f64() else {
+            return Ok(value);
+        };
+
+        let max = || match (&self.filter, &value) {
+            (Filter::Bar { max: Some(m), .. } | Filter::Spark { max: Some(m), .. }, _) => Some(*m),
+            (_, Value::Percent(_)) => Some(100.0),
+            _ => max,
+        };
+
+        let value = match self.filter {
+            Filter::MovingAverage(n) => {
+                self.remember(v, n);
+
+                value.map_f64(|_| self.samples.iter().sum::<f64>() / self.samples.len() as f64)
+            }
+            Filter::Ema(tau) => {
+                let smoothed = match self.last {
+                    Some((prev, at)) if !tau.is_zero() => {
+                        let dt = now.duration_since(at).as_secs_f64();
+                        let alpha = 1.0 - (-dt / tau.as_secs_f64()).exp();
+
+                        prev + alpha * (v - prev)
+                    }
+                    _ => v,
+                };
+
+                self.last = Some((smoothed, now));
+
+                value.map_f64(|_| smoothed)
+            }
+            Filter::Bar { width, .. } => {
+                let max = max().ok_or_else(|| {
+                    DshwError::Template(
+                        "the maximum of `bar` is unknown; use `bar:WIDTH:MAX`".into(),
+                    )
+                })?;
+
+                Value::Text(bar(v, max, width))
+            }
+            Filter::Spark { width, .. } => {
+                let max = max();
+                self.remember(v, width);
+
+                Value::Text(sparkline(&self.samples, max, width))
+            }
+        };
+
+        Ok(value)
+    }
+
+    /// Keeps the last `n` samples.
+    fn remember(&mut self, v: f64, n: usize) {
+        self.samples.push_back(v);
+
+        if self.samples.len() > n {
+            self.samples.pop_front();
+        }
+    }
+}
+
+/// Draws a meter `width` characters wide, with 1/8 character precision.
+fn bar(v: f64, max: f64, width: usize) -> String {
+    let fraction = if max > 0.0 {
+        (v / max).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let eighths = (fraction * width as f64 * 8.0).round() as usize;
+
+    let (full, partial) = (eighths / 8, eighths % 8);
+
+    let mut s = BAR_BLOCKS[7].to_string().repeat(full);
+
+    if partial > 0 {
+        s.push(BAR_BLOCKS[partial - 1]);
+    }
+
+    format!("{:<width$}", s, width = width)
+}
+
+/// Draws the samples as a sparkline right-aligned to `width` characters. Without a known maximum,
+/// the samples are scaled between their minimum and maximum.
+fn sparkline(samples: &VecDeque<f64>, max: Option<f64>, width: usize) -> String {
+    let (lo, hi) = match max {
+        Some(m) => (0.0, m),
+        None => samples
+            .iter()
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| {
+                (lo.min(*v), hi.max(*v))
+            }),
+    };
+
+    let s: String = samples
+        .iter()
+        .map(|v| {
+            let fraction = if hi > lo {
+                ((v - lo) / (hi - lo)).clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+
+            SPARK_BLOCKS[(fraction * 7.0).round() as usize]
+        })
+        .collect();
+
+    format!("{:>width$}", s, width = width)
+}
+
+/// Filters applied one after another.
+#[derive(Debug)]
+pub struct Pipeline {
+    stages: Vec<Stage>,
+}
+
+impl Pipeline {
+    /// Parses filters separated by `|` (`avg:5|bar:10`).
+    pub fn parse(s: &str, data_unit: DataUnit) -> Result<Self, DshwError> {
+        let stages = s
+            .split('|')
+            .map(|f| {
+                Ok(Stage {
+                    filter: Filter::parse(f, data_unit)?,
+                    samples: VecDeque::new(),
+                    last: None,
+                })
+            })
+            .collect::<Result<_, DshwError>>()?;
+
+        Ok(Self { stages })
+    }
+
+    /// Applies the filters to the value. `max` is the known upper bound of the value, used by
+    /// bars and sparklines. Non-numeric values are passed through unchanged.
+    pub fn apply(&mut self, value: Value, max: Option<f64>) -> Result<Value, DshwError> {
+        let now = Instant::now();
+
+        self.stages
+            .iter_mut()
+            .try_fold(value, |v, stage| stage.apply(v, max, now))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> Filter {
+        Filter::parse(s, DataUnit::Bytes).unwrap()
+    }
+
+    #[test]
+    fn parse_filters() {
+        assert!(matches!(parse("avg:5"), Filter::MovingAverage(5)));
+        assert!(matches!(parse("EMA:5s"), Filter::Ema(d) if d == Duration::from_secs(5)));
+        assert!(matches!(
+            parse("bar:10"),
+            Filter::Bar {
+                width: 10,
+                max: None
+            }
+        ));
+        assert!(matches!(
+            parse("spark:8:1KiB"),
+            Filter::Spark { width: 8, max: Some(m) } if m == 1024.0
+        ));
+    }
+
+    #[test]
+    fn parse_invalid() {
+        for s in [
+            "",
+            "avg",
+            "avg:0",
+            "avg:5:10",
+            "ema:5",
+            "bar:x",
+            "bar:5:x",
+            "bar:5:10:1",
+            "foo:1",
+        ] {
+            assert!(Filter::parse(s, DataUnit::Bytes).is_err(), "{}", s);
+        }
+    }
+
+    #[test]
+    fn bar_rounding() {
+        assert_eq!(bar(0.0, 100.0, 4), "    ");
+        assert_eq!(bar(100.0, 100.0, 4), "████");
+        assert_eq!(bar(250.0, 100.0, 4), "████");
+        assert_eq!(bar(-5.0, 100.0, 4), "    ");
+        assert_eq!(bar(50.0, 100.0, 3), "█▌ ");
+        // 1/64 of the width rounds down to nothing, 1/16 up to the first eighth.
+        assert_eq!(bar(1.0, 64.0, 1), " ");
+        assert_eq!(bar(1.0, 16.0, 1), "▏");
+        assert_eq!(bar(5.0, 0.0, 2), "  ");
+    }
+
+    #[test]
+    fn sparkline_scaling() {
+        let samples = VecDeque::from([0.0, 50.0, 100.0, 250.0]);
+
+        assert_eq!(sparkline(&samples, Some(100.0), 4), "▁▅██");
+        assert_eq!(sparkline(&samples, Some(100.0), 6), "  ▁▅██");
+        assert_eq!(sparkline(&samples, None, 4), "▁▂▄█");
+        assert_eq!(sparkline(&VecDeque::from([3.0, 3.0]), None, 2), "▁▁");
+    }
+}
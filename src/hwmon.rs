@@ -0,0 +1,168 @@
+// Copyright (c) 2024 inunix3
+//
+// This file is licensed under the MIT License (see LICENSE.md).
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+const HWMON_DIR: &str = "/sys/class/hwmon";
+
+/// The kind of a hwmon channel other than temperature, which is provided by sysinfo.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum_macros::Display)]
+#[strum(serialize_all = "lowercase")]
+pub enum ChannelKind {
+    Fan,
+    Voltage,
+    Current,
+    Power,
+}
+
+impl ChannelKind {
+    const ALL: [Self; 4] = [Self::Fan, Self::Voltage, Self::Current, Self::Power];
+
+    /// The prefix of the channel files (`fan1_input`).
+    fn prefix(&self) -> &'static str {
+        match self {
+            Self::Fan => "fan",
+            Self::Voltage => "in",
+            Self::Current => "curr",
+            Self::Power => "power",
+        }
+    }
+
+    /// The unit of the values after scaling.
+    pub fn unit(&self) -> &'static str {
+        match self {
+            Self::Fan => "RPM",
+            Self::Voltage => "V",
+            Self::Current => "A",
+            Self::Power => "W",
+        }
+    }
+
+    /// hwmon reports millivolts, milliamperes and microwatts.
+    fn scale(&self) -> f64 {
+        match self {
+            Self::Fan => 1.0,
+            Self::Voltage | Self::Current => 1e-3,
+            Self::Power => 1e-6,
+        }
+    }
+}
+
+/// A fan, voltage, current or power channel of a hwmon chip.
+#[derive(Debug, Clone)]
+pub struct Channel {
+    /// The name of the chip followed by the label of the channel (`nct6798 fan2`, `nct6798 Vcore`).
+    pub label: String,
+    pub kind: ChannelKind,
+    dir: PathBuf,
+    /// The channel files without the attribute (`fan2`).
+    id: String,
+}
+
+impl Channel {
+    /// The current reading (RPM, V, A or W).
+    pub fn input(&self) -> Option<f64> {
+        match self.kind {
+            // Some drivers only report the average power.
+            ChannelKind::Power => self.value("input").or_else(|| self.value("average")),
+            _ => self.value("input"),
+        }
+    }
+
+    pub fn min(&self) -> Option<f64> {
+        self.value("min")
+    }
+
+    pub fn max(&self) -> Option<f64> {
+        match self.kind {
+            ChannelKind::Power => self.value("max").or_else(|| self.value("cap")),
+            _ => self.value("max"),
+        }
+    }
+
+    /// Determine if the chip raised an alarm for the channel.
+    pub fn alarm(&self) -> Option<bool> {
+        self.read("alarm").map(|a| a != "0")
+    }
+
+    fn value(&self, attr: &str) -> Option<f64> {
+        let raw: f64 = self.read(attr)?.parse().ok()?;
+
+        Some(raw * self.kind.scale())
+    }
+
+    fn read(&self, attr: &str) -> Option<String> {
+        let path = self.dir.join(format!("{}_{}", self.id, attr));
+
+        fs::read_to_string(path).ok().map(|s| s.trim().to_string())
+    }
+}
+
+/// Enumerates fan, voltage, current and power channels of all hwmon chips.
+pub fn channels() -> Vec<Channel> {
+    let Ok(chips) = fs::read_dir(HWMON_DIR) else {
+        return vec![];
+    };
+
+    let mut channels = vec![];
+
+    for chip in chips.flatten() {
+        let dir = chip.path();
+        let Ok(name) = fs::read_to_string(dir.join("name")) else {
+            continue;
+        };
+
+        channels.extend(chip_channels(&dir, name.trim()));
+    }
+
+    channels
+}
+
+fn chip_channels(dir: &Path, chip: &str) -> Vec<Channel> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return vec![];
+    };
+
+    let mut channels = vec![];
+
+    for entry in entries.flatten() {
+        let file = entry.file_name().to_string_lossy().to_string();
+
+        let Some((id, attr)) = file.split_once('_') else {
+            continue;
+        };
+
+        // A channel is identified by its reading (`fan1_input`, or `power1_average`).
+        let kind = ChannelKind::ALL.into_iter().find(|k| {
+            id.strip_prefix(k.prefix())
+                .is_some_and(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()))
+        });
+
+        let Some(kind) = kind else {
+            continue;
+        };
+
+        let is_reading = attr == "input" || (kind == ChannelKind::Power && attr == "average");
+
+        if !is_reading || channels.iter().any(|c: &Channel| c.id == id) {
+            continue;
+        }
+
+        let label = fs::read_to_string(dir.join(format!("{}_label", id)))
+            .map(|l| l.trim().to_string())
+            .unwrap_or_else(|_| id.to_string());
+
+        channels.push(Channel {
+            label: format!("{} {}", chip, label),
+            kind,
+            dir: dir.to_path_buf(),
+            id: id.to_string(),
+        });
+    }
+
+    channels
+}
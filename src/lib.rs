@@ -2,8 +2,34 @@
 //
 // This file is licensed under the MIT License (see LICENSE.md).
 
+pub mod api;
 pub mod app;
+pub mod check;
 pub mod cli;
 pub mod cmd;
+pub mod color;
+pub mod completions;
+pub mod cpufreq;
+pub mod cpustat;
+pub mod drive;
+pub mod error;
+pub mod filter;
+pub mod hwmon;
+pub mod list;
+pub mod memory;
+pub mod network;
+pub mod pressure;
+pub mod process;
 pub mod query;
+pub mod schedule;
+pub mod summary;
+pub mod swap;
+pub mod threshold;
+pub mod time;
 pub mod units;
+pub mod value;
+pub mod watch;
+
+pub use api::{query, query_with_options, query_with_unit};
+pub use query::{QueryOptions, Target};
+pub use value::Value;
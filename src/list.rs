@@ -0,0 +1,121 @@
+// Copyright (c) 2024 inunix3
+//
+// This file is licensed under the MIT License (see LICENSE.md).
+
+use crate::cli::ListArgs;
+
+use clap::ValueEnum;
+
+use std::cmp::Ordering;
+
+#[derive(Debug, ValueEnum, Clone, Copy, Default)]
+pub enum SortOrder {
+    /// Lexicographic order (`cpu1`, `cpu10`, `cpu2`).
+    Name,
+    /// Numbers in names are compared by value (`cpu1`, `cpu2`, `cpu10`).
+    #[default]
+    Natural,
+}
+
+impl ListArgs {
+    /// Keeps the names matching the filter and sorts them.
+    pub fn arrange(&self, names: Vec<String>) -> Vec<String> {
+        self.arrange_by(names, |n| n)
+    }
+
+    /// Keeps the items whose names match the filter and sorts them by their names.
+    pub fn arrange_by<T, F>(&self, mut items: Vec<T>, name: F) -> Vec<T>
+    where
+        F: Fn(&T) -> &str,
+    {
+        if let Some(re) = &self.filter {
+            items.retain(|i| re.is_match(name(i)));
+        }
+
+        match self.sort {
+            SortOrder::Name => items.sort_by(|a, b| name(a).cmp(name(b))),
+            SortOrder::Natural => items.sort_by(|a, b| natural_cmp(name(a), name(b))),
+        }
+
+        items
+    }
+}
+
+/// Compares strings so that runs of digits are compared by their numeric value.
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let (mut a, mut b) = (a, b);
+
+    loop {
+        let (Some(ca), Some(cb)) = (a.chars().next(), b.chars().next()) else {
+            return a.len().cmp(&b.len());
+        };
+
+        let ord = if ca.is_ascii_digit() && cb.is_ascii_digit() {
+            let (na, ra) = split_digits(a);
+            let (nb, rb) = split_digits(b);
+            (a, b) = (ra, rb);
+
+            // Leading zeros do not change the value; the longer number is the greater one.
+            let (na, nb) = (na.trim_start_matches('0'), nb.trim_start_matches('0'));
+
+            na.len().cmp(&nb.len()).then_with(|| na.cmp(nb))
+        } else {
+            a = &a[ca.len_utf8()..];
+            b = &b[cb.len_utf8()..];
+
+            ca.cmp(&cb)
+        };
+
+        if ord != Ordering::Equal {
+            return ord;
+        }
+    }
+}
+
+/// Splits the string into the leading run of digits and the rest.
+fn split_digits(s: &str) -> (&str, &str) {
+    let end = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+
+    s.split_at(end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sorted(items: &[&str]) -> Vec<String> {
+        let mut items: Vec<String> = items.iter().map(|s| s.to_string()).collect();
+        items.sort_by(|a, b| natural_cmp(a, b));
+
+        items
+    }
+
+    #[test]
+    fn numbers() {
+        assert_eq!(
+            sorted(&["cpu10", "cpu2", "cpu1", "cpu0"]),
+            ["cpu0", "cpu1", "cpu2", "cpu10"]
+        );
+        assert_eq!(natural_cmp("9", "10"), Ordering::Less);
+        assert_eq!(natural_cmp("123", "123"), Ordering::Equal);
+    }
+
+    #[test]
+    fn leading_zeros() {
+        assert_eq!(natural_cmp("a01", "a1"), Ordering::Equal);
+        assert_eq!(natural_cmp("a002", "a10"), Ordering::Less);
+        assert_eq!(natural_cmp("a0", "a00"), Ordering::Equal);
+        assert_eq!(natural_cmp("a010", "a9"), Ordering::Greater);
+    }
+
+    #[test]
+    fn mixed_digit_runs() {
+        assert_eq!(
+            sorted(&["nvme1n10", "nvme1n2", "nvme0n1p12", "nvme0n1p3", "nvme10n1"]),
+            ["nvme0n1p3", "nvme0n1p12", "nvme1n2", "nvme1n10", "nvme10n1"]
+        );
+        assert_eq!(natural_cmp("sda", "sda1"), Ordering::Less);
+        assert_eq!(natural_cmp("sd1a", "sd1b"), Ordering::Less);
+        assert_eq!(natural_cmp("eth1", "ethernet"), Ordering::Less);
+    }
+}
@@ -2,13 +2,15 @@
 //
 // This file is licensed under the MIT License (see LICENSE.md).
 
-use anyhow::Result;
 use dshw::{
     app::Application,
     cli::{Cli, Parser},
+    error::DshwError,
 };
 
-fn main() -> Result<()> {
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
     let cli = Cli::parse();
 
     if !sysinfo::IS_SUPPORTED_SYSTEM {
@@ -16,5 +18,14 @@ fn main() -> Result<()> {
     }
 
     let app = Application::new();
-    app.run(cli)
+
+    match app.run(cli) {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("Error: {:#}", e);
+
+            e.downcast_ref::<DshwError>()
+                .map_or(ExitCode::FAILURE, DshwError::exit_code)
+        }
+    }
 }
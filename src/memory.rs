@@ -0,0 +1,61 @@
+// Copyright (c) 2024 inunix3
+//
+// This file is licensed under the MIT License (see LICENSE.md).
+
+use crate::swap::zram_stats;
+
+use std::{collections::HashMap, fs};
+
+/// Fields of `/proc/meminfo` in bytes.
+#[derive(Debug, Clone)]
+pub struct MemInfo {
+    fields: HashMap<String, u64>,
+}
+
+impl MemInfo {
+    /// Reads `/proc/meminfo`. Returns nothing if it is not available.
+    pub fn read() -> Option<Self> {
+        let meminfo = fs::read_to_string("/proc/meminfo").ok()?;
+
+        let fields = meminfo
+            .lines()
+            .filter_map(|line| {
+                let (key, value) = line.split_once(':')?;
+                let mut value = value.split_whitespace();
+                let n: u64 = value.next()?.parse().ok()?;
+
+                // Sizes are in kB; counts like `HugePages_Total` have no unit.
+                let n = match value.next() {
+                    Some("kB") => n * 1024,
+                    _ => n,
+                };
+
+                Some((key.to_string(), n))
+            })
+            .collect();
+
+        Some(Self { fields })
+    }
+
+    /// The value of the field (`Buffers`, `Committed_AS`, etc.). Returns nothing if the kernel
+    /// does not report it.
+    pub fn get(&self, key: &str) -> Option<u64> {
+        self.fields.get(key).copied()
+    }
+
+    /// The size of huge pages counted by the field (`HugePages_Total`, `HugePages_Free`).
+    pub fn huge_pages(&self, key: &str) -> Option<u64> {
+        Some(self.get(key)? * self.get("Hugepagesize")?)
+    }
+}
+
+/// Memory used by all zram devices to store compressed data. Returns nothing if there are no zram
+/// devices.
+pub fn zram_used() -> Option<u64> {
+    fs::read_dir("/sys/block")
+        .ok()?
+        .flatten()
+        .filter_map(|e| zram_stats(&e.file_name().to_string_lossy()))
+        .map(|z| z.mem_used)
+        .reduce(|a, b| a + b)
+}
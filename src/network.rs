@@ -0,0 +1,108 @@
+// Copyright (c) 2024 inunix3
+//
+// This file is licensed under the MIT License (see LICENSE.md).
+
+use std::{fs, net::IpAddr, path::Path};
+
+/// Reads an attribute of the interface from `/sys/class/net/<IFACE>/<ATTR>`. Returns nothing if the
+/// attribute does not exist or cannot be read (e.g. `carrier` of an interface that is down).
+pub fn sysfs_attr(iface: &str, attr: &str) -> Option<String> {
+    let path = format!("/sys/class/net/{}/{}", iface, attr);
+
+    fs::read_to_string(path).ok().map(|s| s.trim().to_string())
+}
+
+/// Determine if the interface is backed by a device, as opposed to loopback, bridges, tunnels and
+/// other virtual interfaces.
+pub fn is_physical(iface: &str) -> bool {
+    Path::new("/sys/class/net")
+        .join(iface)
+        .join("device")
+        .exists()
+}
+
+/// Determine if the interface is up. Interfaces that do not report their operational state (like
+/// loopback) are up if they are administratively up.
+pub fn is_up(iface: &str) -> bool {
+    /// `IFF_UP` from `<net/if.h>`.
+    const IFF_UP: u32 = 0x1;
+
+    match sysfs_attr(iface, "operstate").as_deref() {
+        Some("up") => true,
+        Some("unknown") => sysfs_attr(iface, "flags")
+            .and_then(|f| u32::from_str_radix(f.trim_start_matches("0x"), 16).ok())
+            .is_some_and(|f| f & IFF_UP != 0),
+        _ => false,
+    }
+}
+
+/// Addresses assigned to the interface along with their prefix lengths, as reported by
+/// `getifaddrs`.
+#[cfg(unix)]
+pub fn addresses(iface: &str) -> Vec<(IpAddr, u8)> {
+    use std::{
+        ffi::CStr,
+        net::{Ipv4Addr, Ipv6Addr},
+        ptr,
+    };
+
+    let mut addrs = vec![];
+    let mut ifap = ptr::null_mut();
+
+    // SAFETY: the list is only traversed if `getifaddrs` succeeded, the pointers in it are valid
+    // until `freeifaddrs`, and the socket addresses are read according to their family.
+    unsafe {
+        if libc::getifaddrs(&mut ifap) != 0 {
+            return addrs;
+        }
+
+        let mut ifa = ifap;
+
+        while let Some(i) = ifa.as_ref() {
+            ifa = i.ifa_next;
+
+            if i.ifa_addr.is_null() || CStr::from_ptr(i.ifa_name).to_bytes() != iface.as_bytes() {
+                continue;
+            }
+
+            match i32::from((*i.ifa_addr).sa_family) {
+                libc::AF_INET => {
+                    let addr = &*(i.ifa_addr as *const libc::sockaddr_in);
+                    let prefix = i.ifa_netmask.as_ref().map_or(0, |m| {
+                        (*(m as *const _ as *const libc::sockaddr_in))
+                            .sin_addr
+                            .s_addr
+                            .count_ones() as u8
+                    });
+                    let ip = Ipv4Addr::from(u32::from_be(addr.sin_addr.s_addr));
+
+                    addrs.push((IpAddr::V4(ip), prefix));
+                }
+                libc::AF_INET6 => {
+                    let addr = &*(i.ifa_addr as *const libc::sockaddr_in6);
+                    let prefix = i.ifa_netmask.as_ref().map_or(0, |m| {
+                        (*(m as *const _ as *const libc::sockaddr_in6))
+                            .sin6_addr
+                            .s6_addr
+                            .iter()
+                            .map(|b| b.count_ones() as u8)
+                            .sum()
+                    });
+                    let ip = Ipv6Addr::from(addr.sin6_addr.s6_addr);
+
+                    addrs.push((IpAddr::V6(ip), prefix));
+                }
+                _ => {}
+            }
+        }
+
+        libc::freeifaddrs(ifap);
+    }
+
+    addrs
+}
+
+#[cfg(not(unix))]
+pub fn addresses(_iface: &str) -> Vec<(IpAddr, u8)> {
+    vec![]
+}
@@ -0,0 +1,154 @@
+// Copyright (c) 2024 inunix3
+//
+// This file is licensed under the MIT License (see LICENSE.md).
+
+use crate::query::PressureResource;
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+const PRESSURE_DIR: &str = "/proc/pressure";
+const CGROUP_DIR: &str = "/sys/fs/cgroup";
+
+/// Share of time some or all tasks were stalled on a resource.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Stall {
+    /// Average over the last 10 seconds (percentage).
+    pub avg10: f64,
+    /// Average over the last 60 seconds (percentage).
+    pub avg60: f64,
+    /// Average over the last 300 seconds (percentage).
+    pub avg300: f64,
+    /// Total stall time (microseconds).
+    pub total: u64,
+}
+
+/// Pressure Stall Information of a resource.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Pressure {
+    /// Stalls of at least some tasks.
+    pub some: Option<Stall>,
+    /// Stalls of all non-idle tasks at once. Not reported for CPU by older kernels.
+    pub full: Option<Stall>,
+}
+
+/// The directory of the cgroup. The path may be given with or without the `/sys/fs/cgroup`
+/// prefix (`/sys/fs/cgroup/system.slice`, `/system.slice` and `system.slice` are the same).
+pub fn cgroup_dir(path: &str) -> PathBuf {
+    let path = Path::new(path);
+
+    if path.starts_with(CGROUP_DIR) {
+        return path.to_path_buf();
+    }
+
+    Path::new(CGROUP_DIR).join(path.strip_prefix("/").unwrap_or(path))
+}
+
+/// Reads the pressure of the resource from `/proc/pressure`, or from the cgroup directory if
+/// given. Returns nothing if the kernel does not report it.
+pub fn pressure(resource: PressureResource, cgroup: Option<&Path>) -> Option<Pressure> {
+    let name = match resource {
+        PressureResource::Cpu => "cpu",
+        PressureResource::Memory => "memory",
+        PressureResource::Io => "io",
+        PressureResource::Irq => "irq",
+    };
+    let path = match cgroup {
+        Some(dir) => dir.join(format!("{}.pressure", name)),
+        None => Path::new(PRESSURE_DIR).join(name),
+    };
+
+    parse_pressure(&fs::read_to_string(path).ok()?)
+}
+
+/// Parses the contents of a pressure file. Returns nothing if a value is malformed.
+fn parse_pressure(contents: &str) -> Option<Pressure> {
+    let mut pressure = Pressure::default();
+
+    // Lines look like `some avg10=0.31 avg60=0.12 avg300=0.04 total=2830129`.
+    for line in contents.lines() {
+        let mut fields = line.split_whitespace();
+        let kind = fields.next();
+        let mut stall = Stall::default();
+
+        for field in fields {
+            let Some((key, value)) = field.split_once('=') else {
+                continue;
+            };
+
+            match key {
+                "avg10" => stall.avg10 = value.parse().ok()?,
+                "avg60" => stall.avg60 = value.parse().ok()?,
+                "avg300" => stall.avg300 = value.parse().ok()?,
+                "total" => stall.total = value.parse().ok()?,
+                _ => {}
+            }
+        }
+
+        match kind {
+            Some("some") => pressure.some = Some(stall),
+            Some("full") => pressure.full = Some(stall),
+            _ => {}
+        }
+    }
+
+    Some(pressure)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn some_and_full() {
+        let p = parse_pressure(
+            "some avg10=0.31 avg60=0.12 avg300=0.04 total=2830129\n\
+             full avg10=0.00 avg60=0.01 avg300=0.00 total=1024\n",
+        )
+        .unwrap();
+
+        let some = p.some.unwrap();
+        assert_eq!(some.avg10, 0.31);
+        assert_eq!(some.avg60, 0.12);
+        assert_eq!(some.avg300, 0.04);
+        assert_eq!(some.total, 2830129);
+        assert_eq!(p.full.unwrap().total, 1024);
+    }
+
+    #[test]
+    fn without_full() {
+        // Older kernels report only `some` for CPU.
+        let p = parse_pressure("some avg10=1.50 avg60=0.00 avg300=0.00 total=15\n").unwrap();
+
+        assert_eq!(p.some.unwrap().avg10, 1.5);
+        assert_eq!(p.full, None);
+    }
+
+    #[test]
+    fn full_only() {
+        // IRQ pressure has only the `full` row.
+        let p = parse_pressure("full avg10=0.00 avg60=0.00 avg300=0.00 total=42\n").unwrap();
+
+        assert_eq!(p.some, None);
+        assert_eq!(p.full.unwrap().total, 42);
+    }
+
+    #[test]
+    fn malformed() {
+        assert!(parse_pressure("some avg10=x avg60=0.00 avg300=0.00 total=1\n").is_none());
+        assert_eq!(parse_pressure("").unwrap().some, None);
+    }
+
+    #[test]
+    fn cgroup_paths() {
+        for path in [
+            "/sys/fs/cgroup/system.slice",
+            "/system.slice",
+            "system.slice",
+        ] {
+            assert_eq!(cgroup_dir(path), Path::new("/sys/fs/cgroup/system.slice"));
+        }
+    }
+}
@@ -0,0 +1,97 @@
+// Copyright (c) 2024 inunix3
+//
+// This file is licensed under the MIT License (see LICENSE.md).
+
+use std::fs;
+
+/// Numbers of processes by their state and the total number of threads.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ProcessCounts {
+    pub total: u64,
+    pub running: u64,
+    /// Processes sleeping interruptibly, uninterruptibly (waiting for I/O) or idle.
+    pub sleeping: u64,
+    pub zombie: u64,
+    pub threads: u64,
+}
+
+/// Counts processes and threads from `/proc/<PID>/stat`. Returns nothing if `/proc` is not
+/// available.
+pub fn process_counts() -> Option<ProcessCounts> {
+    let mut counts = ProcessCounts::default();
+
+    for entry in fs::read_dir("/proc").ok()?.flatten() {
+        let name = entry.file_name();
+
+        if !name.to_string_lossy().bytes().all(|b| b.is_ascii_digit()) {
+            continue;
+        }
+
+        // The process may have exited in the meantime.
+        let Ok(stat) = fs::read_to_string(entry.path().join("stat")) else {
+            continue;
+        };
+
+        // The command name in parentheses may contain spaces, so fields are counted from the
+        // closing parenthesis: state is the 3rd field and the number of threads the 20th.
+        let Some((_, fields)) = stat.rsplit_once(')') else {
+            continue;
+        };
+        let fields: Vec<&str> = fields.split_whitespace().collect();
+
+        counts.total += 1;
+        counts.threads += fields.get(17).and_then(|t| t.parse().ok()).unwrap_or(1);
+
+        match fields.first().copied() {
+            Some("R") => counts.running += 1,
+            Some("S" | "D" | "I") => counts.sleeping += 1,
+            Some("Z") => counts.zombie += 1,
+            _ => {}
+        }
+    }
+
+    Some(counts)
+}
+
+/// The number of open file handles and the maximum number of them (`fs.file-max`), from
+/// `/proc/sys/fs/file-nr`.
+pub fn open_files() -> Option<(u64, u64)> {
+    let file_nr = fs::read_to_string("/proc/sys/fs/file-nr").ok()?;
+    let fields: Vec<u64> = file_nr
+        .split_whitespace()
+        .map(|f| f.parse().ok())
+        .collect::<Option<_>>()?;
+
+    // Allocated, allocated but unused, and maximum.
+    match fields[..] {
+        [allocated, unused, max] => Some((allocated - unused, max)),
+        _ => None,
+    }
+}
+
+/// The number of logged-in user sessions in the utmpx database.
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+pub fn sessions() -> Option<u64> {
+    let mut count = 0;
+
+    // SAFETY: the entries returned by `getutxent` are valid until the next call, and only the type
+    // is read from them.
+    unsafe {
+        libc::setutxent();
+
+        while let Some(entry) = libc::getutxent().as_ref() {
+            if entry.ut_type == libc::USER_PROCESS {
+                count += 1;
+            }
+        }
+
+        libc::endutxent();
+    }
+
+    Some(count)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+pub fn sessions() -> Option<u64> {
+    None
+}
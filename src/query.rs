@@ -2,27 +2,55 @@
 //
 // This file is licensed under the MIT License (see LICENSE.md).
 
-use crate::cli::CliCommand;
+use crate::{
+    cli::InfoCommand,
+    error::DshwError,
+    time::TimeFormat,
+    units::{DataUnit, FrequencyUnit},
+};
 
-use anyhow::{anyhow, bail, Result};
 use clap::ValueEnum;
 
 #[derive(Debug, ValueEnum, Clone)]
 pub enum OsQuery {
     /// Time when the system booted since UNIX epoch (seconds).
     BootTime,
-    /// A load average within 1 minute expressed as percentage, 2 decimal places.
+    /// A load average within 1 minute, 2 decimal places.
     /// On Windows, returns nothing.
     #[clap(verbatim_doc_comment)]
     LoadAverage1m,
-    /// A load average within 5 minutes expressed as percentage, 2 decimal places.
+    /// A load average within 5 minutes, 2 decimal places.
     /// On Windows, returns nothing.
     #[clap(verbatim_doc_comment)]
     LoadAverage5m,
-    /// A load average within 15 minutes expressed as percentage, 2 decimal places.
+    /// A load average within 15 minutes, 2 decimal places.
     /// On Windows, returns nothing.
     #[clap(verbatim_doc_comment)]
     LoadAverage15m,
+    /// A load average within 1 minute divided by the number of logical CPUs, 2 decimal places.
+    /// 1.00 means fully loaded. On Windows, returns nothing.
+    #[clap(verbatim_doc_comment)]
+    LoadAverage1mPerCpu,
+    /// A load average within 5 minutes divided by the number of logical CPUs, 2 decimal places.
+    /// 1.00 means fully loaded. On Windows, returns nothing.
+    #[clap(verbatim_doc_comment)]
+    LoadAverage5mPerCpu,
+    /// A load average within 15 minutes divided by the number of logical CPUs, 2 decimal places.
+    /// 1.00 means fully loaded. On Windows, returns nothing.
+    #[clap(verbatim_doc_comment)]
+    LoadAverage15mPerCpu,
+    /// A load average within 1 minute divided by the number of physical cores, 2 decimal places.
+    /// Returns nothing if the core count is not available. On Windows, returns nothing.
+    #[clap(verbatim_doc_comment)]
+    LoadAverage1mPerCore,
+    /// A load average within 5 minutes divided by the number of physical cores, 2 decimal places.
+    /// Returns nothing if the core count is not available. On Windows, returns nothing.
+    #[clap(verbatim_doc_comment)]
+    LoadAverage5mPerCore,
+    /// A load average within 15 minutes divided by the number of physical cores, 2 decimal places.
+    /// Returns nothing if the core count is not available. On Windows, returns nothing.
+    #[clap(verbatim_doc_comment)]
+    LoadAverage15mPerCore,
     /// The name of the OS. Returns nothing if not available.
     Name,
     /// The kernel version. Returns nothing if not available.
@@ -43,18 +71,101 @@ pub enum OsQuery {
     TotalCpuUsage,
     /// CPU Architecture (e.g. x86, amd64, aarch64, ...). Returns nothing if not available.
     CpuArch,
+    /// Time since the system booted (seconds).
+    Uptime,
+    /// Time since the system booted in days, hours and minutes (e.g. `3d 4h 12m`).
+    UptimeHuman,
+    /// Time spent by all CPUs idle since boot (seconds, 2 decimal places). Linux only.
+    IdleTime,
+    /// Time when the system booted, formatted according to --time-format.
+    BootTimeFormatted,
+    /// Total number of processes. Linux only.
+    Processes,
+    /// Number of running processes. Linux only.
+    RunningProcesses,
+    /// Number of sleeping processes, including those waiting for I/O. Linux only.
+    SleepingProcesses,
+    /// Number of zombie processes. Linux only.
+    ZombieProcesses,
+    /// Total number of threads of all processes. Linux only.
+    Threads,
+    /// Number of open file handles. Linux only.
+    OpenFiles,
+    /// Maximum number of open file handles (`fs.file-max`). Linux only.
+    MaxOpenFiles,
+    /// Open file handles as percentage of the maximum, 2 decimal places. Linux only.
+    OpenFilesPercent,
+    /// Number of logged-in user sessions. On Windows, returns nothing.
+    Sessions,
+    /// Time all CPUs spent in user mode (percentage, 2 decimal places). Linux only.
+    CpuUser,
+    /// Time all CPUs spent in user mode with low priority (percentage, 2 decimal places). Linux
+    /// only.
+    CpuNice,
+    /// Time all CPUs spent in kernel mode (percentage, 2 decimal places). Linux only.
+    CpuSystem,
+    /// Time all CPUs spent idle (percentage, 2 decimal places). Linux only.
+    CpuIdle,
+    /// Time all CPUs spent waiting for I/O (percentage, 2 decimal places). Linux only.
+    CpuIowait,
+    /// Time all CPUs spent servicing interrupts (percentage, 2 decimal places). Linux only.
+    CpuIrq,
+    /// Time all CPUs spent servicing softirqs (percentage, 2 decimal places). Linux only.
+    CpuSoftirq,
+    /// Time stolen from all CPUs by the hypervisor (percentage, 2 decimal places). Linux only.
+    CpuSteal,
+    /// Time all CPUs spent running virtual machines (percentage, 2 decimal places). Linux only.
+    CpuGuest,
 }
 
 #[derive(Debug, ValueEnum, Clone)]
 pub enum CpuQuery {
     /// CPU usage (percentage, 2 decimal places).
     Usage,
-    /// The frequency of the CPU (the unit is not defined; can be MHz, GHz, etc).
+    /// The current frequency of the CPU (in the unit given by --freq-unit).
     Frequency,
     /// The brand of the CPU (e.g. "Intel(R) Core(TM) i9-9900K CPU @ 3.60GHz").
     Brand,
     /// ID of CPU's vendor (e.g. "GenuineIntel").
     VendorId,
+    /// The minimal frequency allowed by the scaling policy. Linux only.
+    MinFrequency,
+    /// The maximal frequency allowed by the scaling policy. Linux only.
+    MaxFrequency,
+    /// The minimal frequency supported by the hardware. Linux only.
+    HardwareMinFrequency,
+    /// The maximal frequency supported by the hardware, including boost. Linux only.
+    HardwareMaxFrequency,
+    /// The base (non-boost) frequency. Returns nothing if the driver does not report it. Linux only.
+    BaseFrequency,
+    /// The scaling governor (e.g. `powersave`, `schedutil`). Linux only.
+    Governor,
+    /// Space-separated list of available scaling governors. Linux only.
+    AvailableGovernors,
+    /// Energy-performance preference (e.g. `balance_performance`). Returns nothing if the driver
+    /// does not support it. Linux only.
+    Epp,
+    /// Determine if frequency boost (turbo) is enabled (boolean, 1 or 0). Linux only.
+    Boost,
+    /// Time spent in user mode (percentage, 2 decimal places). Linux only.
+    User,
+    /// Time spent in user mode with low priority (percentage, 2 decimal places). Linux only.
+    Nice,
+    /// Time spent in kernel mode (percentage, 2 decimal places). Linux only.
+    System,
+    /// Time spent idle (percentage, 2 decimal places). Linux only.
+    Idle,
+    /// Time spent waiting for I/O (percentage, 2 decimal places). Linux only.
+    Iowait,
+    /// Time spent servicing interrupts (percentage, 2 decimal places). Linux only.
+    Irq,
+    /// Time spent servicing softirqs (percentage, 2 decimal places). Linux only.
+    Softirq,
+    /// Time stolen by the hypervisor for other virtual machines (percentage, 2 decimal places).
+    /// Linux only.
+    Steal,
+    /// Time spent running virtual machines (percentage, 2 decimal places). Linux only.
+    Guest,
 }
 
 #[derive(Debug, ValueEnum, Clone)]
@@ -67,6 +178,36 @@ pub enum MemoryQuery {
     Available,
     /// Unallocated memory. On Windows, it's the same as `available`.
     Free,
+    /// Memory usage as percentage of the total memory, 2 decimal places.
+    UsagePercent,
+    /// Available memory as percentage of the total memory, 2 decimal places.
+    AvailablePercent,
+    /// Memory used by block device buffers. Linux only.
+    Buffers,
+    /// Memory used by the page cache. Linux only.
+    Cached,
+    /// Shared memory, including tmpfs. Linux only.
+    Shared,
+    /// Memory used by kernel data structures. Linux only.
+    Slab,
+    /// Memory waiting to be written back to disk. Linux only.
+    Dirty,
+    /// Memory being written back to disk. Linux only.
+    Writeback,
+    /// Memory allocated by processes, even if not used yet. Linux only.
+    CommittedAs,
+    /// Maximum memory that can be allocated under strict overcommit. Linux only.
+    CommitLimit,
+    /// Total size of huge pages. Linux only.
+    HugepagesTotal,
+    /// Size of free huge pages. Linux only.
+    HugepagesFree,
+    /// Memory used by the compressed zswap pool. Returns nothing if zswap is not supported.
+    /// Linux only.
+    #[clap(verbatim_doc_comment)]
+    Zswap,
+    /// Memory used by all zram devices. Returns nothing if there are no zram devices. Linux only.
+    Zram,
 }
 
 #[derive(Debug, ValueEnum, Clone)]
@@ -77,6 +218,72 @@ pub enum SwapQuery {
     Total,
     /// Available swap memory.
     Available,
+    /// Swap usage as percentage of the total swap, 2 decimal places. Returns nothing if there is
+    /// no swap.
+    UsagePercent,
+    /// Available swap as percentage of the total swap, 2 decimal places. Returns nothing if there
+    /// is no swap.
+    AvailablePercent,
+}
+
+#[derive(Debug, ValueEnum, Clone)]
+pub enum SwapDeviceQuery {
+    /// The kind of the swap (partition or file).
+    Type,
+    /// Swap priority. Devices with higher priority are used first.
+    Priority,
+    /// Size of the swap.
+    Size,
+    /// Used swap space.
+    Used,
+    /// Used space as percentage of the size, 2 decimal places.
+    UsagePercent,
+    /// Size of the stored data divided by its compressed size, 2 decimal places. zram only.
+    CompressionRatio,
+    /// Size of the stored data before compression. zram only.
+    OriginalSize,
+    /// Size of the stored data after compression. zram only.
+    CompressedSize,
+    /// Memory used by the device, including allocator overhead. zram only.
+    MemUsed,
+    /// Compression algorithm (e.g. `lzo-rle`, `zstd`). zram only.
+    Algorithm,
+}
+
+#[derive(Debug, ValueEnum, Clone)]
+pub enum PressureQuery {
+    /// Share of time at least some tasks were stalled, averaged over 10 seconds (percentage, 2
+    /// decimal places).
+    SomeAvg10,
+    /// Share of time at least some tasks were stalled, averaged over 60 seconds (percentage, 2
+    /// decimal places).
+    SomeAvg60,
+    /// Share of time at least some tasks were stalled, averaged over 300 seconds (percentage, 2
+    /// decimal places).
+    SomeAvg300,
+    /// Total time at least some tasks were stalled (microseconds).
+    SomeTotal,
+    /// Share of time all non-idle tasks were stalled at once, averaged over 10 seconds
+    /// (percentage, 2 decimal places).
+    FullAvg10,
+    /// Share of time all non-idle tasks were stalled at once, averaged over 60 seconds
+    /// (percentage, 2 decimal places).
+    FullAvg60,
+    /// Share of time all non-idle tasks were stalled at once, averaged over 300 seconds
+    /// (percentage, 2 decimal places).
+    FullAvg300,
+    /// Total time all non-idle tasks were stalled at once (microseconds).
+    FullTotal,
+}
+
+/// The resource whose pressure `dshw pressure` reports.
+#[derive(Debug, ValueEnum, Clone, Copy, PartialEq, Eq)]
+pub enum PressureResource {
+    Cpu,
+    Memory,
+    Io,
+    /// Time spent servicing interrupts. Only `full` is reported.
+    Irq,
 }
 
 #[derive(Debug, ValueEnum, Clone)]
@@ -95,6 +302,38 @@ pub enum DriveQuery {
     Total,
     /// Total available space.
     Available,
+    /// Used space as percentage of the total space, 2 decimal places.
+    UsagePercent,
+    /// Available space as percentage of the total space, 2 decimal places.
+    AvailablePercent,
+    /// Total number of inodes. Returns nothing if not available.
+    Inodes,
+    /// Number of free inodes. Returns nothing if not available.
+    FreeInodes,
+    /// Used inodes as percentage of all inodes, 2 decimal places. Returns nothing if the
+    /// filesystem has no fixed number of inodes.
+    InodeUsagePercent,
+    /// Determine if the filesystem is mounted read-only (boolean, 1 or 0).
+    IsReadOnly,
+    /// Mount options, including filesystem-specific ones (e.g. `rw,relatime,errors=remount-ro`).
+    /// Linux only.
+    MountOptions,
+}
+
+/// How `dshw drive` selects the drive.
+#[derive(Debug, ValueEnum, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DriveKey {
+    /// The device name (e.g. `/dev/sda3`).
+    #[default]
+    Name,
+    /// The mount point (e.g. `/home`).
+    Mount,
+    /// The filesystem label, as found in `/dev/disk/by-label`.
+    Label,
+    /// The filesystem UUID, as found in `/dev/disk/by-uuid`.
+    Uuid,
+    /// Any path; selects the drive mounted at the longest mount point containing it.
+    Path,
 }
 
 #[derive(Debug, ValueEnum, Clone)]
@@ -107,6 +346,22 @@ pub enum SensorQuery {
     MaxTemp,
     /// Current sensor's temperature (Celsius, 2 decimal places).
     Temperature,
+    /// Current reading in the unit of the sensor (see `unit`). Fan speeds are whole numbers,
+    /// other readings have 2 decimal places.
+    Reading,
+    /// Lower limit configured for the sensor (e.g. `in0_min`). Returns nothing if not available,
+    /// which is always the case for temperature sensors.
+    Min,
+    /// Upper limit configured for the sensor (e.g. `fan1_max`, `power1_cap`). Returns nothing if
+    /// not available, which is always the case for temperature sensors (see `critical-temp`).
+    Max,
+    /// Determine if the chip raised an alarm for the sensor (boolean, 1 or 0). Returns nothing if
+    /// not available.
+    Alarm,
+    /// The kind of the sensor (temperature, fan, voltage, current or power).
+    Kind,
+    /// The unit of the readings (°C, RPM, V, A or W).
+    Unit,
 }
 
 #[derive(Debug, ValueEnum, Clone)]
@@ -125,68 +380,246 @@ pub enum NetworkQuery {
     TotalReceivedPackets,
     /// Total number of transmitted packets.
     TotalTransmittedPackets,
+    /// IPv4 addresses with prefix lengths (e.g. `192.168.1.5/24`), separated by spaces.
+    Ipv4,
+    /// IPv6 addresses with prefix lengths (e.g. `fe80::1/64`), separated by spaces.
+    Ipv6,
+    /// Maximum transmission unit (bytes). Linux only.
+    Mtu,
+    /// Operational state (`up`, `down`, `dormant`, `unknown`, etc.). Linux only.
+    Operstate,
+    /// Determine if the link has a carrier (boolean, 1 or 0). Returns nothing if the interface is
+    /// down. Linux only.
+    Carrier,
+    /// Link speed (Mbit/s). Returns nothing if not known (e.g. for wireless or virtual
+    /// interfaces). Linux only.
+    Speed,
+    /// Duplex mode (`full`, `half` or `unknown`). Linux only.
+    Duplex,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum Query {
     None,
     Os(OsQuery),
     Cpu(CpuQuery),
     Memory(MemoryQuery),
     Swap(SwapQuery),
+    SwapDevice(SwapDeviceQuery),
+    Pressure(PressureQuery),
     Drive(DriveQuery),
     Sensor(SensorQuery),
     Network(NetworkQuery),
 }
 
+/// The entity queries are issued against.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Target {
+    Os,
+    /// A CPU by its name (e.g. `cpu0`).
+    Cpu(String),
+    Memory,
+    Swap,
+    /// A swap device or file by its path (e.g. `/dev/zram0`).
+    SwapDevice(String),
+    /// Pressure Stall Information of a resource, system-wide or of a cgroup given by its path.
+    Pressure {
+        resource: PressureResource,
+        cgroup: Option<String>,
+    },
+    /// A drive by its name (e.g. `/dev/sda3`), or by the key given in `by`.
+    Drive {
+        name: String,
+        by: DriveKey,
+    },
+    /// A sensor by its label (e.g. `coretemp Core 0`).
+    Sensor(String),
+    /// A network interface by its name (e.g. `wlan0`).
+    Network(String),
+}
+
+/// Options which affect how the values of queries are presented, but not which target they are
+/// issued against.
+#[derive(Debug, Clone)]
+pub struct QueryOptions {
+    /// The unit of information used by memory, swap, drive and network queries.
+    pub data_unit: DataUnit,
+    /// The unit of CPU frequencies.
+    pub freq_unit: FrequencyUnit,
+    /// Format of `boot-time-formatted`.
+    pub time_format: TimeFormat,
+}
+
+impl Default for QueryOptions {
+    fn default() -> Self {
+        Self::new(DataUnit::Bytes)
+    }
+}
+
+impl QueryOptions {
+    /// Options with the given data unit and defaults for everything else.
+    pub fn new(data_unit: DataUnit) -> Self {
+        Self {
+            data_unit,
+            freq_unit: FrequencyUnit::default(),
+            time_format: TimeFormat::default(),
+        }
+    }
+}
+
 impl Query {
-    pub fn from_str(cmd: &CliCommand, s: &str) -> Result<Self> {
+    pub fn from_str(cmd: &InfoCommand, s: &str) -> Result<Self, DshwError> {
         const IGNORE_CASE: bool = true;
 
         let q = match cmd {
-            CliCommand::Os { queries: _ } => Self::Os(
+            InfoCommand::Os {
+                queries: _,
+                time_format: _,
+                utc: _,
+            } => Self::Os(
                 OsQuery::from_str(s, IGNORE_CASE)
-                    .map_err(|_| anyhow!("invalid os query `{}`", s))?,
+                    .map_err(|_| DshwError::invalid_query(s, "not a os query"))?,
             ),
-            CliCommand::Cpu {
+            InfoCommand::Cpu {
                 name: _,
                 queries: _,
+                freq_unit: _,
             } => Self::Cpu(
                 CpuQuery::from_str(s, IGNORE_CASE)
-                    .map_err(|_| anyhow!("invalid cpu query `{}`", s))?,
+                    .map_err(|_| DshwError::invalid_query(s, "not a cpu query"))?,
             ),
-            CliCommand::Memory { queries: _ } => Self::Memory(
+            InfoCommand::Memory { queries: _ } => Self::Memory(
                 MemoryQuery::from_str(s, IGNORE_CASE)
-                    .map_err(|_| anyhow!("invalid memory query `{}`", s))?,
+                    .map_err(|_| DshwError::invalid_query(s, "not a memory query"))?,
             ),
-            CliCommand::Swap { queries: _ } => Self::Swap(
+            InfoCommand::Swap { queries: _ } => Self::Swap(
                 SwapQuery::from_str(s, IGNORE_CASE)
-                    .map_err(|_| anyhow!("invalid swap query `{}`", s))?,
+                    .map_err(|_| DshwError::invalid_query(s, "not a swap query"))?,
             ),
-            CliCommand::Drive {
+            InfoCommand::SwapDevice {
                 name: _,
                 queries: _,
+            } => Self::SwapDevice(
+                SwapDeviceQuery::from_str(s, IGNORE_CASE)
+                    .map_err(|_| DshwError::invalid_query(s, "not a swap device query"))?,
+            ),
+            InfoCommand::Pressure {
+                resource: _,
+                cgroup: _,
+                queries: _,
+            } => Self::Pressure(
+                PressureQuery::from_str(s, IGNORE_CASE)
+                    .map_err(|_| DshwError::invalid_query(s, "not a pressure query"))?,
+            ),
+            InfoCommand::Drive {
+                name: _,
+                by: _,
+                queries: _,
             } => Self::Drive(
                 DriveQuery::from_str(s, IGNORE_CASE)
-                    .map_err(|_| anyhow!("invalid drive query `{}`", s))?,
+                    .map_err(|_| DshwError::invalid_query(s, "not a drive query"))?,
             ),
-            CliCommand::Sensor {
+            InfoCommand::Sensor {
                 name: _,
                 queries: _,
             } => Self::Sensor(
                 SensorQuery::from_str(s, IGNORE_CASE)
-                    .map_err(|_| anyhow!("invalid sensor query `{}`", s))?,
+                    .map_err(|_| DshwError::invalid_query(s, "not a sensor query"))?,
             ),
-            CliCommand::Network {
+            InfoCommand::Network {
                 name: _,
                 queries: _,
             } => Self::Network(
                 NetworkQuery::from_str(s, IGNORE_CASE)
-                    .map_err(|_| anyhow!("invalid network query `{}`", s))?,
+                    .map_err(|_| DshwError::invalid_query(s, "not a network query"))?,
             ),
-            _ => bail!("this command does not take any arguments"),
+            _ => {
+                return Err(DshwError::invalid_query(
+                    s,
+                    "this command does not take any arguments",
+                ))
+            }
         };
 
         Ok(q)
     }
+
+    /// Determine if the query can be issued against the target.
+    pub fn is_for(&self, target: &Target) -> bool {
+        matches!(
+            (self, target),
+            (Self::None, _)
+                | (Self::Os(_), Target::Os)
+                | (Self::Cpu(_), Target::Cpu(_))
+                | (Self::Memory(_), Target::Memory)
+                | (Self::Swap(_), Target::Swap)
+                | (Self::SwapDevice(_), Target::SwapDevice(_))
+                | (Self::Pressure(_), Target::Pressure { .. })
+                | (Self::Drive(_), Target::Drive { .. })
+                | (Self::Sensor(_), Target::Sensor(_))
+                | (Self::Network(_), Target::Network(_))
+        )
+    }
+
+    /// The query whose value is the upper bound of the value of this query (e.g. total memory for
+    /// memory usage). Used as the maximum of bars.
+    pub fn bound(&self) -> Option<Query> {
+        match self {
+            Self::Memory(MemoryQuery::Usage | MemoryQuery::Available | MemoryQuery::Free) => {
+                Some(Self::Memory(MemoryQuery::Total))
+            }
+            Self::Swap(SwapQuery::Usage | SwapQuery::Available) => {
+                Some(Self::Swap(SwapQuery::Total))
+            }
+            Self::SwapDevice(SwapDeviceQuery::Used) => {
+                Some(Self::SwapDevice(SwapDeviceQuery::Size))
+            }
+            Self::Drive(DriveQuery::Usage | DriveQuery::Available) => {
+                Some(Self::Drive(DriveQuery::Total))
+            }
+            _ => None,
+        }
+    }
+
+    /// The name of the query as it is written on the command line (e.g. `total-cpu-usage`).
+    pub fn name(&self) -> String {
+        let value = match self {
+            Self::None => return String::new(),
+            Self::Os(q) => q.to_possible_value(),
+            Self::Cpu(q) => q.to_possible_value(),
+            Self::Memory(q) => q.to_possible_value(),
+            Self::Swap(q) => q.to_possible_value(),
+            Self::SwapDevice(q) => q.to_possible_value(),
+            Self::Pressure(q) => q.to_possible_value(),
+            Self::Drive(q) => q.to_possible_value(),
+            Self::Sensor(q) => q.to_possible_value(),
+            Self::Network(q) => q.to_possible_value(),
+        };
+
+        value.map(|v| v.get_name().to_string()).unwrap_or_default()
+    }
 }
+
+macro_rules! impl_from_query {
+    ($($variant:ident($ty:ty)),* $(,)?) => {
+        $(
+            impl From<$ty> for Query {
+                fn from(q: $ty) -> Self {
+                    Self::$variant(q)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_query!(
+    Os(OsQuery),
+    Cpu(CpuQuery),
+    Memory(MemoryQuery),
+    Swap(SwapQuery),
+    SwapDevice(SwapDeviceQuery),
+    Pressure(PressureQuery),
+    Drive(DriveQuery),
+    Sensor(SensorQuery),
+    Network(NetworkQuery),
+);
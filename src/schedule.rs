@@ -0,0 +1,98 @@
+// Copyright (c) 2024 inunix3
+//
+// This file is licensed under the MIT License (see LICENSE.md).
+
+use std::{
+    sync::mpsc::{Receiver, RecvTimeoutError},
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+/// Fixed-rate scheduler: ticks happen every `interval` regardless of how long the work between
+/// them takes. If `align` is set, ticks happen on wall-clock multiples of the interval instead.
+#[derive(Debug)]
+pub struct Scheduler {
+    interval: Duration,
+    align: bool,
+    next: Instant,
+    interrupt: Option<Receiver<()>>,
+}
+
+impl Scheduler {
+    /// Without an interval, ticks follow each other immediately.
+    pub fn new(interval: Option<Duration>, align: bool) -> Self {
+        Self {
+            interval: interval.unwrap_or_default(),
+            align,
+            next: Instant::now(),
+            interrupt: None,
+        }
+    }
+
+    /// Makes waiting stop as soon as a message arrives on the channel.
+    pub fn interruptible(mut self, rx: Receiver<()>) -> Self {
+        self.interrupt = Some(rx);
+        self
+    }
+
+    /// Waits for the first tick, which is immediate unless ticks are aligned. Returns false if
+    /// interrupted.
+    pub fn start(&mut self) -> bool {
+        if self.align {
+            self.wait()
+        } else {
+            self.sleep(Duration::ZERO)
+        }
+    }
+
+    /// Sleeps until the next tick. Ticks missed because the work took longer than the interval are
+    /// skipped rather than run in a burst. Returns false if interrupted.
+    pub fn wait(&mut self) -> bool {
+        if self.interval.is_zero() {
+            return self.sleep(Duration::ZERO);
+        }
+
+        if self.align {
+            return self.sleep(until_boundary(self.interval));
+        }
+
+        let now = Instant::now();
+        self.next += self.interval;
+
+        if self.next < now {
+            let behind = (now - self.next).as_nanos() / self.interval.as_nanos();
+
+            self.next += self.interval * (behind as u32 + 1);
+        }
+
+        self.sleep(self.next.saturating_duration_since(now))
+    }
+
+    fn sleep(&self, duration: Duration) -> bool {
+        match &self.interrupt {
+            Some(rx) => match rx.recv_timeout(duration) {
+                Ok(()) => false,
+                Err(RecvTimeoutError::Timeout) => true,
+                Err(RecvTimeoutError::Disconnected) => {
+                    thread::sleep(duration);
+                    true
+                }
+            },
+            None => {
+                thread::sleep(duration);
+                true
+            }
+        }
+    }
+}
+
+/// Time left until the wall clock reaches the next multiple of the interval since the UNIX epoch.
+fn until_boundary(interval: Duration) -> Duration {
+    let since_epoch = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    let period = interval.as_nanos();
+
+    Duration::from_nanos((period - since_epoch % period) as u64)
+}
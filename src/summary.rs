@@ -0,0 +1,73 @@
+// Copyright (c) 2024 inunix3
+//
+// This file is licensed under the MIT License (see LICENSE.md).
+
+use crate::value::Value;
+
+/// Collects numeric values of queries across runs and computes summary statistics of them.
+#[derive(Debug, Default)]
+pub struct Summary {
+    /// Samples of each query, in the order the queries were first seen.
+    samples: Vec<(String, Vec<f64>)>,
+}
+
+impl Summary {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Records the value of the query. Non-numeric values are ignored.
+    pub fn record(&mut self, name: &str, value: &Value) {
+        let Some(v) = value.as_f64() else {
+            return;
+        };
+
+        match self.samples.iter_mut().find(|(n, _)| n == name) {
+            Some((_, samples)) => samples.push(v),
+            None => self.samples.push((name.to_string(), vec![v])),
+        }
+    }
+
+    /// One line per query: `<query>: n=… min=… max=… mean=… median=… p95=… stddev=…`.
+    pub fn report(&self) -> Vec<String> {
+        self.samples
+            .iter()
+            .map(|(name, samples)| {
+                let mut sorted = samples.clone();
+                sorted.sort_by(f64::total_cmp);
+
+                let n = sorted.len() as f64;
+                let mean = sorted.iter().sum::<f64>() / n;
+                let variance = sorted.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n;
+
+                format!(
+                    "{}: n={} min={:.2} max={:.2} mean={:.2} median={:.2} p95={:.2} stddev={:.2}",
+                    name,
+                    sorted.len(),
+                    sorted[0],
+                    sorted[sorted.len() - 1],
+                    mean,
+                    median(&sorted),
+                    percentile(&sorted, 95.0),
+                    variance.sqrt(),
+                )
+            })
+            .collect()
+    }
+}
+
+fn median(sorted: &[f64]) -> f64 {
+    let mid = sorted.len() / 2;
+
+    match sorted.len() % 2 {
+        0 => (sorted[mid - 1] + sorted[mid]) / 2.0,
+        _ => sorted[mid],
+    }
+}
+
+/// Nearest-rank percentile.
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    let rank = (p / 100.0 * sorted.len() as f64).ceil() as usize;
+
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
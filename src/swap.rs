@@ -0,0 +1,100 @@
+// Copyright (c) 2024 inunix3
+//
+// This file is licensed under the MIT License (see LICENSE.md).
+
+use crate::drive::unescape_octal;
+
+use std::{fs, path::Path};
+
+/// A swap device or file from `/proc/swaps`.
+#[derive(Debug, Clone)]
+pub struct SwapDevice {
+    /// The path of the device or file (`/dev/zram0`, `/swapfile`).
+    pub name: String,
+    /// `partition` or `file`.
+    pub kind: String,
+    /// Size in bytes.
+    pub size: u64,
+    /// Used space in bytes.
+    pub used: u64,
+    pub priority: i64,
+}
+
+/// Lists active swap devices and files. Returns nothing if `/proc/swaps` is not available.
+pub fn swap_devices() -> Vec<SwapDevice> {
+    let Ok(swaps) = fs::read_to_string("/proc/swaps") else {
+        return vec![];
+    };
+
+    // The first line is the header; sizes are in KiB.
+    swaps
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+
+            match fields[..] {
+                [name, kind, size, used, priority] => Some(SwapDevice {
+                    name: unescape_octal(name),
+                    kind: kind.to_string(),
+                    size: size.parse::<u64>().ok()? * 1024,
+                    used: used.parse::<u64>().ok()? * 1024,
+                    priority: priority.parse().ok()?,
+                }),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// Statistics of a zram device.
+#[derive(Debug, Clone)]
+pub struct ZramStats {
+    /// Size of the stored data before compression (bytes).
+    pub original: u64,
+    /// Size of the stored data after compression (bytes).
+    pub compressed: u64,
+    /// Memory used including allocator overhead (bytes).
+    pub mem_used: u64,
+    /// The compression algorithm in use.
+    pub algorithm: Option<String>,
+}
+
+impl ZramStats {
+    /// Original size divided by compressed size. Returns nothing if no data is stored.
+    pub fn ratio(&self) -> Option<f64> {
+        (self.compressed > 0).then(|| self.original as f64 / self.compressed as f64)
+    }
+}
+
+/// Reads the statistics of a zram device from `/sys/block/<NAME>`. The name may be given as a path
+/// (`/dev/zram0`). Returns nothing if the device is not a zram device.
+pub fn zram_stats(device: &str) -> Option<ZramStats> {
+    let name = Path::new(device).file_name()?.to_str()?;
+
+    if !name.starts_with("zram") {
+        return None;
+    }
+
+    let dir = Path::new("/sys/block").join(name);
+    let mm_stat = fs::read_to_string(dir.join("mm_stat")).ok()?;
+    let fields: Vec<u64> = mm_stat
+        .split_whitespace()
+        .map(|f| f.parse().ok())
+        .collect::<Option<_>>()?;
+
+    // The selected algorithm is in brackets: `[lzo-rle] lzo lz4`.
+    let algorithm = fs::read_to_string(dir.join("comp_algorithm"))
+        .ok()
+        .and_then(|a| {
+            a.split_whitespace()
+                .find_map(|a| a.strip_prefix('[')?.strip_suffix(']').map(str::to_string))
+        });
+
+    Some(ZramStats {
+        original: *fields.first()?,
+        compressed: *fields.get(1)?,
+        mem_used: *fields.get(2)?,
+        algorithm,
+    })
+}
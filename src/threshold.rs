@@ -0,0 +1,220 @@
+// Copyright (c) 2024 inunix3
+//
+// This file is licensed under the MIT License (see LICENSE.md).
+
+use crate::{
+    error::DshwError,
+    units::{DataUnit, DataValue},
+};
+
+use clap::ValueEnum;
+
+use std::fmt;
+
+/// A threshold range in the monitoring-plugins syntax: `[@]start:end`. `start` defaults to 0 and
+/// can be `~` (negative infinity), `end` defaults to infinity. A value outside of the range (or
+/// inside, if the range starts with `@`) raises an alert. Both bounds may have a unit suffix: `%`
+/// or a unit of information (`2GiB`), which is converted to the unit used by the command.
+#[derive(Debug, Clone)]
+pub struct Threshold {
+    start: f64,
+    end: f64,
+    inside: bool,
+}
+
+impl Threshold {
+    pub fn parse(s: &str, data_unit: DataUnit) -> Result<Self, DshwError> {
+        let (inside, range) = match s.strip_prefix('@') {
+            Some(r) => (true, r),
+            None => (false, s),
+        };
+
+        let (start, end) = range.split_once(':').unwrap_or(("", range));
+        let start = match start {
+            "" => 0.0,
+            "~" => f64::NEG_INFINITY,
+            _ => parse_bound(start, data_unit)?,
+        };
+        let end = match end {
+            "" => f64::INFINITY,
+            _ => parse_bound(end, data_unit)?,
+        };
+
+        if start > end {
+            let reason = format!("invalid threshold `{}`: start is greater than end", s);
+
+            return Err(DshwError::Template(reason));
+        }
+
+        Ok(Self { start, end, inside })
+    }
+
+    /// The threshold with both bounds multiplied by the factor (e.g. to convert them to bytes).
+    pub fn scaled(&self, factor: f64) -> Self {
+        Self {
+            start: self.start * factor,
+            end: self.end * factor,
+            inside: self.inside,
+        }
+    }
+
+    /// Determine if the value raises an alert.
+    pub fn alerts(&self, value: f64) -> bool {
+        let outside = value < self.start || value > self.end;
+
+        outside != self.inside
+    }
+}
+
+/// Formats the threshold back to the range syntax with converted bounds, as used in perfdata.
+impl fmt::Display for Threshold {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.inside {
+            write!(f, "@")?;
+        }
+
+        if self.start == f64::NEG_INFINITY {
+            write!(f, "~:")?;
+        } else if self.start != 0.0 || self.end == f64::INFINITY {
+            write!(f, "{}:", self.start)?;
+        }
+
+        if self.end != f64::INFINITY {
+            write!(f, "{}", self.end)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Parses a bound with an optional unit suffix. Percentages are taken as is, units of information
+/// are converted to `data_unit`.
+pub fn parse_bound(s: &str, data_unit: DataUnit) -> Result<f64, DshwError> {
+    let split = s
+        .find(|c: char| !(c.is_ascii_digit() || matches!(c, '.' | '-' | '+')))
+        .unwrap_or(s.len());
+    let (number, suffix) = s.split_at(split);
+
+    let number: f64 = number
+        .parse()
+        .map_err(|_| DshwError::Template(format!("invalid threshold bound `{}`", s)))?;
+
+    match suffix {
+        "" | "%" => Ok(number),
+        _ => {
+            let unit = if suffix.eq_ignore_ascii_case("b") {
+                DataUnit::Bytes
+            } else {
+                DataUnit::from_str(suffix, true).map_err(|_| {
+                    DshwError::Template(format!(
+                        "invalid unit `{}` in threshold bound `{}`",
+                        suffix, s
+                    ))
+                })?
+            };
+
+            Ok(DataValue::from_bytes(number * unit.factor(), data_unit).value())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> Threshold {
+        Threshold::parse(s, DataUnit::Bytes).unwrap()
+    }
+
+    #[test]
+    fn end_only() {
+        let t = parse("10");
+
+        assert!(t.alerts(-1.0));
+        assert!(!t.alerts(0.0));
+        assert!(!t.alerts(10.0));
+        assert!(t.alerts(10.5));
+    }
+
+    #[test]
+    fn start_only() {
+        let t = parse("10:");
+
+        assert!(t.alerts(9.9));
+        assert!(!t.alerts(10.0));
+        assert!(!t.alerts(1e12));
+    }
+
+    #[test]
+    fn negative_infinity() {
+        let t = parse("~:10");
+
+        assert!(!t.alerts(-1e12));
+        assert!(!t.alerts(10.0));
+        assert!(t.alerts(11.0));
+    }
+
+    #[test]
+    fn start_and_end() {
+        let t = parse("10:20");
+
+        assert!(t.alerts(9.0));
+        assert!(!t.alerts(10.0));
+        assert!(!t.alerts(20.0));
+        assert!(t.alerts(21.0));
+    }
+
+    #[test]
+    fn inside() {
+        let t = parse("@10:20");
+
+        assert!(!t.alerts(9.0));
+        assert!(t.alerts(10.0));
+        assert!(t.alerts(15.0));
+        assert!(t.alerts(20.0));
+        assert!(!t.alerts(21.0));
+    }
+
+    #[test]
+    fn units() {
+        assert_eq!(parse("90%").end, 90.0);
+        assert_eq!(parse("1KiB").end, 1024.0);
+
+        let t = Threshold::parse("2GiB", DataUnit::Mib).unwrap();
+
+        assert_eq!(t.end, 2048.0);
+        assert!(t.alerts(2049.0));
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(Threshold::parse("20:10", DataUnit::Bytes).is_err());
+        assert!(Threshold::parse("abc", DataUnit::Bytes).is_err());
+        assert!(Threshold::parse("10xb", DataUnit::Bytes).is_err());
+    }
+
+    #[test]
+    fn scaled() {
+        let t = Threshold::parse("@1:2", DataUnit::Gib)
+            .unwrap()
+            .scaled(1024.0);
+
+        assert_eq!(t.to_string(), "@1024:2048");
+        assert_eq!(parse("~:10").scaled(2.0).to_string(), "~:20");
+        assert_eq!(parse("10:").scaled(2.0).to_string(), "20:");
+    }
+
+    #[test]
+    fn display_round_trip() {
+        for s in ["10", "10:", "~:10", "10:20", "@10:20", "@~:10", "@10:"] {
+            assert_eq!(parse(s).to_string(), s);
+            assert_eq!(parse(&parse(s).to_string()).to_string(), s);
+        }
+
+        assert_eq!(parse("0:10").to_string(), "10");
+        assert_eq!(
+            Threshold::parse("2GiB", DataUnit::Mib).unwrap().to_string(),
+            "2048"
+        );
+    }
+}
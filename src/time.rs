@@ -0,0 +1,70 @@
+// Copyright (c) 2024 inunix3
+//
+// This file is licensed under the MIT License (see LICENSE.md).
+
+use crate::error::DshwError;
+
+use chrono::{DateTime, Local, Utc};
+
+use std::{fmt::Write, fs};
+
+pub const DEFAULT_TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// How points in time are formatted.
+#[derive(Debug, Clone)]
+pub struct TimeFormat {
+    /// strftime pattern.
+    pub pattern: String,
+    /// Use UTC instead of the local time zone.
+    pub utc: bool,
+}
+
+impl Default for TimeFormat {
+    fn default() -> Self {
+        Self {
+            pattern: DEFAULT_TIME_FORMAT.to_string(),
+            utc: false,
+        }
+    }
+}
+
+impl TimeFormat {
+    /// Formats the time given in seconds since UNIX epoch.
+    pub fn format(&self, secs: u64) -> Result<String, DshwError> {
+        let invalid = || DshwError::Template(format!("invalid time format `{}`", self.pattern));
+
+        let utc = DateTime::<Utc>::from_timestamp(secs as i64, 0).ok_or_else(invalid)?;
+        let mut s = String::new();
+
+        // Invalid specifiers are only detected while writing.
+        if self.utc {
+            write!(s, "{}", utc.format(&self.pattern))
+        } else {
+            write!(s, "{}", utc.with_timezone(&Local).format(&self.pattern))
+        }
+        .map_err(|_| invalid())?;
+
+        Ok(s)
+    }
+}
+
+/// Formats the duration as days, hours and minutes, starting with the largest non-zero part
+/// (`3d 4h 12m`, `5h 0m`, `7m`).
+pub fn format_uptime(secs: u64) -> String {
+    let (days, hours, mins) = (secs / 86400, secs % 86400 / 3600, secs % 3600 / 60);
+
+    if days > 0 {
+        format!("{}d {}h {}m", days, hours, mins)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, mins)
+    } else {
+        format!("{}m", mins)
+    }
+}
+
+/// Time spent by all CPUs in the idle task since boot (seconds), from `/proc/uptime`.
+pub fn idle_time() -> Option<f64> {
+    let uptime = fs::read_to_string("/proc/uptime").ok()?;
+
+    uptime.split_whitespace().nth(1)?.parse().ok()
+}
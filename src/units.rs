@@ -4,7 +4,7 @@
 
 use clap::ValueEnum;
 
-#[derive(Debug, ValueEnum, Clone, Copy, strum_macros::Display)]
+#[derive(Debug, ValueEnum, Clone, Copy, PartialEq, Eq, strum_macros::Display)]
 pub enum DataUnit {
     #[strum(serialize = "bits")]
     Bits,
@@ -28,18 +28,10 @@ pub enum DataUnit {
     Tib,
 }
 
-pub struct DataValue {
-    value: f64,
-    unit: DataUnit,
-}
-
-impl DataValue {
-    pub fn new(value: f64, unit: DataUnit) -> Self {
-        Self { value, unit }
-    }
-
-    pub fn from_bytes(value: f64, unit: DataUnit) -> Self {
-        let factor = match unit {
+impl DataUnit {
+    /// How many bytes one unit contains.
+    pub fn factor(&self) -> f64 {
+        match self {
             DataUnit::Bits => 1.0 / 8.0,
             DataUnit::Bytes => 1.0,
             DataUnit::Kb => 1000.0,
@@ -50,10 +42,35 @@ impl DataValue {
             DataUnit::Gib => 1024.0 * 1024.0 * 1024.0,
             DataUnit::Tb => 1_000_000_000_000.0,
             DataUnit::Tib => 1024.0 * 1024.0 * 1024.0 * 1024.0,
-        };
+        }
+    }
+}
 
+#[derive(Debug, ValueEnum, Clone, Copy, Default, PartialEq, Eq, strum_macros::Display)]
+pub enum FrequencyUnit {
+    /// Megahertz, whole numbers.
+    #[default]
+    #[strum(serialize = "mhz")]
+    Mhz,
+    /// Gigahertz, 2 decimal places.
+    #[strum(serialize = "ghz")]
+    Ghz,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DataValue {
+    value: f64,
+    unit: DataUnit,
+}
+
+impl DataValue {
+    pub fn new(value: f64, unit: DataUnit) -> Self {
+        Self { value, unit }
+    }
+
+    pub fn from_bytes(value: f64, unit: DataUnit) -> Self {
         Self {
-            value: value / factor,
+            value: value / unit.factor(),
             unit,
         }
     }
@@ -62,9 +79,14 @@ impl DataValue {
         self.value
     }
 
+    /// The value converted to bytes.
+    pub fn bytes(&self) -> f64 {
+        self.value * self.unit.factor()
+    }
+
     pub fn value_str(&self) -> String {
         match self.unit {
-            DataUnit::Bits | DataUnit::Bytes => self.value.to_string(),
+            DataUnit::Bits | DataUnit::Bytes => format!("{:.0}", self.value),
             _ => format!("{:.2}", self.value),
         }
     }
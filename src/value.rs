@@ -0,0 +1,83 @@
+// Copyright (c) 2024 inunix3
+//
+// This file is licensed under the MIT License (see LICENSE.md).
+
+use crate::units::DataValue;
+
+use std::fmt;
+
+/// A typed result of a query. Formatting a value with `Display` gives the same text dshw prints.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    /// The value is not available (printed as nothing).
+    None,
+    /// A flag (printed as 1 or 0).
+    Bool(bool),
+    Integer(u64),
+    /// An integer which can be negative (e.g. swap priority).
+    SignedInteger(i64),
+    /// A real number (printed with 2 decimal places).
+    Float(f64),
+    /// A percentage (printed with 2 decimal places).
+    Percent(f64),
+    /// An amount of information in the requested unit.
+    Data(DataValue),
+    Text(String),
+}
+
+impl Value {
+    /// The numeric representation of the value. Text and missing values have none.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Self::None | Self::Text(_) => None,
+            Self::Bool(b) => Some(*b as u8 as f64),
+            Self::Integer(i) => Some(*i as f64),
+            Self::SignedInteger(i) => Some(*i as f64),
+            Self::Float(f) | Self::Percent(f) => Some(*f),
+            Self::Data(d) => Some(d.value()),
+        }
+    }
+
+    /// Transforms the numeric representation of the value, keeping its kind where it makes sense.
+    /// Integers and flags become real numbers; text and missing values are left untouched.
+    pub fn map_f64<F: FnOnce(f64) -> f64>(self, f: F) -> Self {
+        match self {
+            Self::Percent(v) => Self::Percent(f(v)),
+            Self::Data(d) => Self::Data(DataValue::new(f(d.value()), d.unit())),
+            Self::None | Self::Text(_) => self,
+            v => Self::Float(f(v.as_f64().unwrap_or_default())),
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::None => Ok(()),
+            Self::Bool(b) => write!(f, "{}", *b as i32),
+            Self::Integer(i) => write!(f, "{}", i),
+            Self::SignedInteger(i) => write!(f, "{}", i),
+            Self::Float(v) | Self::Percent(v) => write!(f, "{:.2}", v),
+            Self::Data(d) => write!(f, "{}", d.value_str()),
+            Self::Text(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+impl From<String> for Value {
+    fn from(s: String) -> Self {
+        Self::Text(s)
+    }
+}
+
+impl From<Option<String>> for Value {
+    fn from(s: Option<String>) -> Self {
+        s.map_or(Self::None, Self::Text)
+    }
+}
+
+impl From<DataValue> for Value {
+    fn from(d: DataValue) -> Self {
+        Self::Data(d)
+    }
+}
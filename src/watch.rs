@@ -0,0 +1,113 @@
+// Copyright (c) 2024 inunix3
+//
+// This file is licensed under the MIT License (see LICENSE.md).
+
+use crate::{cli::WatchArgs, error::DshwError, value::Value};
+
+use anyhow::{Context, Result};
+
+use std::{
+    process,
+    time::{Duration, Instant},
+};
+
+/// Keeps track of a watched value across iterations and runs hooks when the watch condition starts
+/// or stops holding.
+#[derive(Debug)]
+pub struct Watcher {
+    above: Option<f64>,
+    below: Option<f64>,
+    hold: Duration,
+    exec: String,
+    recover: Option<String>,
+    /// When the condition started to hold continuously.
+    since: Option<Instant>,
+    triggered: bool,
+}
+
+impl Watcher {
+    pub fn new(args: &WatchArgs) -> Result<Self> {
+        if args.above.is_none() && args.below.is_none() {
+            let reason = "`watch` requires at least one of --above and --below";
+
+            return Err(DshwError::invalid_query("", reason).into());
+        }
+
+        let exec = args.exec.clone().ok_or_else(|| {
+            DshwError::invalid_query("", "`watch` requires a command to run (--exec)")
+        })?;
+
+        Ok(Self {
+            above: args.above,
+            below: args.below,
+            hold: args.hold.map(Into::into).unwrap_or_default(),
+            exec,
+            recover: args.recover.clone(),
+            since: None,
+            triggered: false,
+        })
+    }
+
+    /// The condition holds if the value crosses any of the given thresholds.
+    fn holds(&self, value: f64) -> bool {
+        self.above.is_some_and(|a| value > a) || self.below.is_some_and(|b| value < b)
+    }
+
+    /// Updates the state with the current value of the query, which must be numeric.
+    pub fn update(&mut self, query: &str, value: &Value) -> Result<()> {
+        let now = Instant::now();
+
+        if self.holds(value.as_f64().unwrap_or_default()) {
+            let since = *self.since.get_or_insert(now);
+
+            if !self.triggered && now.duration_since(since) >= self.hold {
+                self.triggered = true;
+
+                run_hook(&self.exec, "trigger", query, value)?;
+            }
+        } else {
+            self.since = None;
+
+            if self.triggered {
+                self.triggered = false;
+
+                if let Some(recover) = &self.recover {
+                    run_hook(recover, "recover", query, value)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Runs the hook with the value formatted the same way dshw prints it.
+fn run_hook(cmd: &str, event: &str, query: &str, value: &Value) -> Result<()> {
+    #[cfg(windows)]
+    let mut shell = {
+        let mut c = process::Command::new("cmd");
+        c.arg("/C");
+        c
+    };
+    #[cfg(not(windows))]
+    let mut shell = {
+        let mut c = process::Command::new("sh");
+        c.arg("-c");
+        c
+    };
+
+    let status = shell
+        .arg(cmd)
+        .env("DSHW_VALUE", value.to_string())
+        .env("DSHW_EVENT", event)
+        .env("DSHW_QUERY", query)
+        .status()
+        .map_err(DshwError::Io)
+        .with_context(|| format!("failed to run `{}`", cmd))?;
+
+    if !status.success() {
+        eprintln!("Warning: `{}` exited with {}", cmd, status);
+    }
+
+    Ok(())
+}